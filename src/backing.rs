@@ -0,0 +1,72 @@
+use crate::errors::CustomError;
+
+/// Source de données placée derrière un cache, enregistrée avec `CacheDB::with_backing_store`.
+///
+/// Le cache devient alors un cache en lecture directe (read-through) : une clé absente est
+/// recherchée dans la source, puis insérée dans le cache. Il devient aussi un cache en écriture
+/// directe (write-through) : chaque valeur insérée est d'abord écrite dans la source.
+pub trait BackingStore<K, V> {
+    /// Recherche dans la source la valeur d'une clé absente du cache.
+    ///
+    /// Retourne `Ok(None)` si la source ne contient pas la clé.
+    fn fetch_on_miss(&self, key: &K) -> Result<Option<V>, CustomError>;
+
+    /// Écrit dans la source une valeur sur le point d'être insérée dans le cache.
+    ///
+    /// Une erreur annule l'insertion dans le cache.
+    fn write_on_put(&self, key: &K, value: &V) -> Result<(), CustomError>;
+}
+
+/// Source de données définie par deux closures.
+///
+/// # Exemples
+///
+/// ```
+/// use eval_rust::{CacheDB, FnBackingStore};
+/// use eval_rust::errors::CustomError;
+///
+/// # fn main() -> Result<(), CustomError> {
+/// let store = FnBackingStore::new(
+///     |key: &String| Ok(Some(key.len())),
+///     |_key: &String, _value: &usize| Ok(()),
+/// );
+/// let mut cache = CacheDB::<String, usize>::new_persistent(5, "cache_fn_backing_store.txt")?
+///     .with_backing_store(store);
+///
+/// // La valeur est calculée par la source puis conservée dans le cache.
+/// assert_eq!(cache.get(&"pomme".to_string()), Some(&5));
+/// assert_eq!(cache.len(), 1);
+/// # std::fs::remove_file("cache_fn_backing_store.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FnBackingStore<F, W> {
+    fetch: F,
+    write: W,
+}
+
+impl<F, W> FnBackingStore<F, W> {
+    /// Crée une source de données à partir d'une closure de lecture et d'une closure d'écriture.
+    ///
+    /// # Arguments
+    ///
+    /// * `fetch` - Appelée avec une clé absente du cache, comme `BackingStore::fetch_on_miss`.
+    /// * `write` - Appelée avec chaque valeur insérée, comme `BackingStore::write_on_put`.
+    pub fn new(fetch: F, write: W) -> Self {
+        FnBackingStore { fetch, write }
+    }
+}
+
+impl<K, V, F, W> BackingStore<K, V> for FnBackingStore<F, W>
+where
+    F: Fn(&K) -> Result<Option<V>, CustomError>,
+    W: Fn(&K, &V) -> Result<(), CustomError>,
+{
+    fn fetch_on_miss(&self, key: &K) -> Result<Option<V>, CustomError> {
+        (self.fetch)(key)
+    }
+
+    fn write_on_put(&self, key: &K, value: &V) -> Result<(), CustomError> {
+        (self.write)(key, value)
+    }
+}
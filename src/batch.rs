@@ -0,0 +1,17 @@
+/// Résultat d'une opération par lot pour une clé, retourné dans l'ordre des clés demandées.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchItem<K, V> {
+    /// La clé demandée.
+    pub key: K,
+    /// Le statut de la clé.
+    pub status: BatchStatus<V>,
+}
+
+/// Statut d'une clé dans une opération par lot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchStatus<V> {
+    /// La clé est dans le cache ; contient la valeur associée.
+    Found(V),
+    /// La clé n'est pas dans le cache.
+    Missing,
+}
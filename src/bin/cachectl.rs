@@ -0,0 +1,227 @@
+use eval_rust::{CacheDB, CapacityPolicy, CustomError, ExportFormat};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io;
+
+const DEFAULT_CAPACITY: usize = 1000;
+
+const USAGE: &str = "Usage: cachectl [--capacity N] <fichier> <commande> [arguments]
+       cachectl simulate --trace <fichier> --capacity N[,N...] [--policy lru|lfu[,...]]
+
+Commandes:
+  get <clé>                       Affiche la valeur associée à la clé
+  peek <clé>                      Affiche la valeur sans la marquer comme récemment utilisée
+  put <clé> <valeur>              Insère une valeur (JSON, ou texte brut sinon)
+  rm <clé>                        Supprime la clé
+  ls [préfixe]                    Liste les éléments, du moins au plus récemment utilisé
+  stats                           Affiche les statistiques du cache
+  export <json|csv> [sortie]      Exporte le cache (sur la sortie standard par défaut)
+  import <json|csv> <entrée>      Importe des éléments exportés
+  clear                           Vide le cache
+  replay <journal>                Rejoue un journal d'opérations
+
+Simulation:
+  Rejoue une trace d'accès (une clé par ligne) pour chaque combinaison de capacité et de
+  politique d'éviction, et affiche le taux de succès obtenu.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let Err(e) = run(&args) {
+        eprintln!("Erreur: {}", e);
+        if matches!(e, CustomError::BadRequest) {
+            eprintln!("{}", USAGE);
+        }
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), CustomError> {
+    if let [command, rest @ ..] = args {
+        if command == "simulate" {
+            return simulate(rest);
+        }
+    }
+
+    let (capacity, args) = match args {
+        [flag, capacity, rest @ ..] if flag == "--capacity" => {
+            (capacity.parse().map_err(|_| CustomError::BadRequest)?, rest)
+        }
+        _ => (DEFAULT_CAPACITY, args),
+    };
+
+    let (file_path, command, args) = match args {
+        [file_path, command, rest @ ..] => (file_path, command.as_str(), rest),
+        _ => return Err(CustomError::BadRequest),
+    };
+
+    // La capacité s'adapte au fichier plutôt que de supprimer des éléments à l'ouverture.
+    let mut cache = CacheDB::<String, Value>::new_persistent_with_policy(capacity, file_path, CapacityPolicy::Grow)?;
+
+    match (command, args) {
+        ("get", [key]) => match cache.get(key) {
+            Some(value) => println!("{}", value),
+            None => return Err(CustomError::NotFound),
+        },
+        ("peek", [key]) => match cache.peek(key) {
+            Some(value) => println!("{}", value),
+            None => return Err(CustomError::NotFound),
+        },
+        ("put", [key, value]) => {
+            let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.clone()));
+            cache.put(key.clone(), value)?;
+        }
+        ("rm", [key]) => {
+            cache.remove(key)?;
+        }
+        ("ls", []) => {
+            for (key, value) in cache.iter() {
+                println!("{} = {}", key, value);
+            }
+        }
+        ("ls", [prefix]) => {
+            for (_, key, value) in cache.scan_prefix(prefix) {
+                println!("{} = {}", key, value);
+            }
+        }
+        ("stats", []) => {
+            let stats = cache.stats();
+            println!("éléments: {}", stats.len);
+            println!("capacité: {}", stats.capacity);
+            println!("mémoire utilisée: {} octets", stats.used_bytes);
+            if let (Some(duration), Some(file_size)) = (stats.last_persist_duration, stats.file_size) {
+                println!("dernière sauvegarde: {:?} ({} octets)", duration, file_size);
+            }
+        }
+        ("export", [format]) => cache.export_to_writer(io::stdout(), parse_format(format)?)?,
+        ("export", [format, output]) => cache.export_to_writer(File::create(output)?, parse_format(format)?)?,
+        ("import", [format, input]) => {
+            let count = cache.import_from_reader(File::open(input)?, parse_format(format)?)?;
+            println!("{} éléments importés", count);
+        }
+        ("clear", []) => {
+            let count = cache.clear()?;
+            println!("{} éléments supprimés", count);
+        }
+        ("replay", [log]) => {
+            let count = cache.replay(log)?;
+            println!("{} opérations rejouées", count);
+        }
+        _ => return Err(CustomError::BadRequest),
+    }
+
+    Ok(())
+}
+
+fn parse_format(format: &str) -> Result<ExportFormat, CustomError> {
+    match format {
+        "json" => Ok(ExportFormat::Json),
+        "csv" => Ok(ExportFormat::Csv),
+        _ => Err(CustomError::BadRequest),
+    }
+}
+
+/// Politique d'éviction simulée.
+#[derive(Clone, Copy)]
+enum Policy {
+    Lru,
+    Lfu,
+}
+
+impl Policy {
+    fn name(self) -> &'static str {
+        match self {
+            Policy::Lru => "lru",
+            Policy::Lfu => "lfu",
+        }
+    }
+}
+
+/// Rejoue une trace d'accès pour chaque configuration demandée et affiche les taux de succès.
+fn simulate(args: &[String]) -> Result<(), CustomError> {
+    let mut trace = None;
+    let mut capacities = Vec::new();
+    let mut policies = vec![Policy::Lru];
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or(CustomError::BadRequest)?;
+        match flag.as_str() {
+            "--trace" => trace = Some(value),
+            "--capacity" => {
+                capacities = value
+                    .split(',')
+                    .map(|capacity| capacity.parse().map_err(|_| CustomError::BadRequest))
+                    .collect::<Result<_, _>>()?
+            }
+            "--policy" => {
+                policies = value
+                    .split(',')
+                    .map(|policy| match policy {
+                        "lru" => Ok(Policy::Lru),
+                        "lfu" => Ok(Policy::Lfu),
+                        _ => Err(CustomError::BadRequest),
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            _ => return Err(CustomError::BadRequest),
+        }
+    }
+
+    let trace = std::fs::read_to_string(trace.ok_or(CustomError::BadRequest)?)?;
+    let keys: Vec<&str> = trace.lines().map(str::trim).filter(|key| !key.is_empty()).collect();
+    if capacities.is_empty() {
+        return Err(CustomError::BadRequest);
+    }
+
+    println!("politique  capacité  accès  succès  taux");
+    for &policy in policies.iter() {
+        for &capacity in capacities.iter() {
+            let hits = count_hits(&keys, capacity, policy);
+            let rate = if keys.is_empty() { 0.0 } else { hits as f64 * 100.0 / keys.len() as f64 };
+            println!("{:<10} {:>8}  {:>5}  {:>6}  {:>5.1}%", policy.name(), capacity, keys.len(), hits, rate);
+        }
+    }
+
+    Ok(())
+}
+
+/// Compte les accès servis par un cache de capacité donnée ; chaque échec insère la clé.
+///
+/// Les éléments sont ordonnés par nombre d'accès (LFU uniquement) puis par date du dernier accès :
+/// le premier élément de l'ensemble ordonné est celui à évincer.
+fn count_hits(keys: &[&str], capacity: usize, policy: Policy) -> usize {
+    let mut entries: HashMap<&str, (u64, u64)> = HashMap::new();
+    let mut order: BTreeSet<(u64, u64, &str)> = BTreeSet::new();
+    let mut hits = 0;
+
+    for (tick, &key) in keys.iter().enumerate() {
+        let tick = tick as u64;
+        let frequency = match entries.get(key) {
+            Some(&(rank, last_access)) => {
+                hits += 1;
+                order.remove(&(rank, last_access, key));
+                rank + 1
+            }
+            None if capacity == 0 => continue,
+            None => {
+                if entries.len() >= capacity {
+                    if let Some((_, _, evicted)) = order.pop_first() {
+                        entries.remove(evicted);
+                    }
+                }
+                1
+            }
+        };
+
+        let rank = match policy {
+            Policy::Lru => 0,
+            Policy::Lfu => frequency,
+        };
+        entries.insert(key, (rank, tick));
+        order.insert((rank, tick, key));
+    }
+
+    hits
+}
@@ -0,0 +1,12 @@
+/// Politique appliquée lorsqu'un fichier chargé contient plus d'éléments que la capacité du cache.
+///
+/// * `TruncateLru` - Les éléments les moins récemment utilisés sont supprimés jusqu'à respecter la capacité.
+/// * `Error` - Le chargement échoue avec `CustomError::CacheDbCapacityError`, sans modifier le cache ni le fichier.
+/// * `Grow` - La capacité du cache est augmentée au nombre d'éléments du fichier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CapacityPolicy {
+    #[default]
+    TruncateLru,
+    Error,
+    Grow,
+}
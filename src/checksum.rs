@@ -0,0 +1,50 @@
+use crate::record::split_line;
+use serde::Serialize;
+
+/// Calcule la somme de contrôle CRC32 (polynôme IEEE 802.3) de données.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Calcule la somme de contrôle d'une valeur encodée en JSON.
+pub(crate) fn value_checksum<V: Serialize>(value: &V) -> Option<u32> {
+    serde_json::to_vec(value).ok().map(|json| crc32(&json))
+}
+
+/// Préfixe du champ ajouté en fin de ligne du fichier de cache, contenant la somme de contrôle de la ligne.
+const LINE_CHECKSUM_PREFIX: &str = "crc32:";
+
+/// Ajoute à une ligne du fichier de cache le champ contenant sa somme de contrôle.
+pub(crate) fn append_line_checksum(line: &mut String) {
+    let checksum = crc32(line.as_bytes());
+    line.push_str(&format!("={}{:08x}", LINE_CHECKSUM_PREFIX, checksum));
+}
+
+/// Retire d'une ligne du fichier de cache le champ contenant sa somme de contrôle, après l'avoir vérifiée.
+///
+/// Une ligne sans somme de contrôle est retournée telle quelle si `required` est faux, c'est-à-dire
+/// pour les fichiers écrits avant la migration `add_line_checksums`. Retourne `None` si la somme de
+/// contrôle ne correspond pas au contenu de la ligne, ou si elle est absente alors qu'elle est requise.
+pub(crate) fn strip_line_checksum(line: &str, required: bool) -> Option<&str> {
+    let fields = split_line(line);
+    let last = fields.last().copied().unwrap_or_default();
+    let checksum = match last.strip_prefix(LINE_CHECKSUM_PREFIX) {
+        Some(checksum) if fields.len() > 1 => checksum,
+        _ if required => return None,
+        _ => return Some(line),
+    };
+
+    let body = &line[..line.len() - last.len() - 1];
+    match u32::from_str_radix(checksum, 16) {
+        Ok(checksum) if checksum == crc32(body.as_bytes()) => Some(body),
+        _ => None,
+    }
+}
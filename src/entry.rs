@@ -0,0 +1,167 @@
+use crate::errors::CustomError;
+use crate::persistent::CacheDB;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// Élément d'un cache, présent ou absent, retourné par `CacheDB::entry`.
+///
+/// Chaque méthode qui modifie le cache persiste le cache une seule fois, comme `put`.
+pub enum Entry<'a, K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+/// Élément présent dans le cache.
+pub struct OccupiedEntry<'a, K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    pub(crate) cache: &'a mut CacheDB<K, V>,
+    pub(crate) key: K,
+    pub(crate) index: usize,
+}
+
+/// Élément absent du cache.
+pub struct VacantEntry<'a, K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    pub(crate) cache: &'a mut CacheDB<K, V>,
+    pub(crate) key: K,
+}
+
+impl<'a, K, V> Entry<'a, K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Retourne la clé de l'élément.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Insère une valeur si l'élément est absent, puis retourne la valeur de l'élément.
+    ///
+    /// Un élément présent est marqué comme récemment utilisé, comme avec `get`.
+    ///
+    /// # Arguments
+    ///
+    /// * `default` - La valeur insérée si l'élément est absent.
+    ///
+    /// # Retour
+    ///
+    /// Retourne une référence vers la valeur de l'élément, ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn or_insert(self, default: V) -> Result<&'a V, CustomError> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Insère la valeur calculée par une closure si l'élément est absent, puis retourne la valeur de l'élément.
+    ///
+    /// La closure n'est appelée que si l'élément est absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `default` - La closure qui calcule la valeur à insérer.
+    ///
+    /// # Retour
+    ///
+    /// Retourne une référence vers la valeur de l'élément, ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn or_insert_with<F>(self, default: F) -> Result<&'a V, CustomError>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_value(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Modifie la valeur d'un élément présent avec une closure, puis persiste le cache.
+    ///
+    /// La valeur modifiée est enregistrée comme avec `put`. Un élément absent n'est pas modifié.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - La closure appliquée à la valeur.
+    ///
+    /// # Retour
+    ///
+    /// Retourne l'élément, `CustomError::Locked` si l'élément est gelé, ou une autre erreur
+    /// `CustomError` si une erreur s'est produite.
+    pub fn and_modify<F>(self, f: F) -> Result<Self, CustomError>
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                let mut value = entry.get().clone();
+                f(&mut value);
+                entry.cache.put(entry.key.clone(), value)?;
+                // L'élément réécrit n'occupe pas forcément le même emplacement.
+                match entry.cache.position(&entry.key) {
+                    Some(index) => {
+                        entry.index = index;
+                        Ok(Entry::Occupied(entry))
+                    }
+                    None => Ok(Entry::Vacant(VacantEntry { cache: entry.cache, key: entry.key })),
+                }
+            }
+            Entry::Vacant(entry) => Ok(Entry::Vacant(entry)),
+        }
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Retourne la clé de l'élément.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Retourne la valeur de l'élément, sans modifier l'ordre d'utilisation des éléments.
+    ///
+    /// L'élément reste lisible même si sa durée de vie s'écoule après l'appel à `CacheDB::entry`.
+    pub fn get(&self) -> &V {
+        self.cache.value_at(self.index)
+    }
+
+    /// Marque l'élément comme récemment utilisé et retourne sa valeur.
+    fn into_value(self) -> Result<&'a V, CustomError> {
+        Ok(self.cache.hit_at(self.index))
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Retourne la clé de l'élément.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Insère une valeur comme avec `put` et retourne une référence vers elle.
+    ///
+    /// # Retour
+    ///
+    /// Retourne une référence vers la valeur insérée, ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn insert(self, value: V) -> Result<&'a V, CustomError> {
+        let VacantEntry { cache, key } = self;
+        cache.put(key.clone(), value)?;
+        let cache: &'a CacheDB<K, V> = cache;
+        cache.peek(&key).ok_or(CustomError::NotFound)
+    }
+}
@@ -1,15 +1,20 @@
 use std::fmt::{Display, Formatter};
-use serde_json;
 
 #[derive(Debug)]
 pub enum CustomError {
     NotFound,
     BadRequest,
+    Locked,
+    PreconditionFailed,
     IoError(std::io::Error),
     CacheDbLoadError,
     CacheDbCapacityError,
     CacheDbSaveError,
+    CacheDbLockError,
+    CacheDbCorruptedError { line: usize },
     SerializationError(serde_json::Error),
+    SchemaVersionMismatch { expected: u32, found: u32 },
+    ValueCorrupted,
 }
 
 impl Display for CustomError {
@@ -17,11 +22,19 @@ impl Display for CustomError {
         match self {
             CustomError::NotFound => write!(f, "404 Not Found"),
             CustomError::BadRequest => write!(f, "400 Bad Request"),
+            CustomError::Locked => write!(f, "423 Locked"),
+            CustomError::PreconditionFailed => write!(f, "412 Precondition Failed"),
             CustomError::IoError(err) => write!(f, "IO Error: {}", err),
             CustomError::CacheDbLoadError => write!(f, "Cache DB Load Error"),
             CustomError::CacheDbCapacityError => write!(f, "Cache DB Capacity Error"),
             CustomError::CacheDbSaveError => write!(f, "Cache DB Save Error"),
+            CustomError::CacheDbLockError => write!(f, "Cache DB Lock Error"),
+            CustomError::CacheDbCorruptedError { line } => write!(f, "Cache DB Corrupted Error: line {}", line),
             CustomError::SerializationError(err) => write!(f, "Serialization Error: {}", err),
+            CustomError::SchemaVersionMismatch { expected, found } => {
+                write!(f, "Schema Version Mismatch: expected {}, found {}", expected, found)
+            }
+            CustomError::ValueCorrupted => write!(f, "Value Corrupted"),
         }
     }
 }
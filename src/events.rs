@@ -0,0 +1,118 @@
+use std::cell::Cell;
+use std::sync::mpsc::Sender;
+
+/// Observateur des modifications d'un cache, enregistré avec `CacheDB::subscribe`.
+///
+/// Toutes les méthodes ont une implémentation par défaut qui ne fait rien : il suffit
+/// d'implémenter celles qui sont utiles.
+///
+/// # Exemples
+///
+/// ```
+/// use eval_rust::{CacheDB, CacheEventListener};
+/// use eval_rust::errors::CustomError;
+///
+/// struct Logger;
+///
+/// impl CacheEventListener<String, i32> for Logger {
+///     fn on_evict(&self, key: &String, value: &i32) {
+///         println!("{} = {} a été évincé", key, value);
+///     }
+/// }
+///
+/// # fn main() -> Result<(), CustomError> {
+/// let mut cache = CacheDB::<String, i32>::new_persistent(1, "cache_events.txt")?;
+/// cache.subscribe(Logger);
+/// cache.put("pomme".to_string(), 1)?;
+/// cache.put("banane".to_string(), 2)?;
+/// # std::fs::remove_file("cache_events.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait CacheEventListener<K, V> {
+    /// Appelé après l'insertion ou la mise à jour d'un élément.
+    fn on_insert(&self, _key: &K, _value: &V) {}
+
+    /// Appelé après la suppression explicite d'un élément, avec `remove` ou `clear`.
+    fn on_remove(&self, _key: &K, _value: &V) {}
+
+    /// Appelé après l'éviction d'un élément pour respecter la capacité ou le budget mémoire du cache.
+    fn on_evict(&self, _key: &K, _value: &V) {}
+
+    /// Appelé après la suppression d'un élément dont la durée de vie stricte est écoulée.
+    fn on_expire(&self, _key: &K, _value: &V) {}
+
+    /// Appelé après la suppression d'un élément dont la valeur ne correspond plus à sa somme de contrôle.
+    fn on_corrupted(&self, _key: &K, _value: &V) {}
+
+    /// Indique que l'observateur ne recevra plus d'évènements : le cache le retire alors de ses observateurs.
+    fn is_closed(&self) -> bool {
+        false
+    }
+}
+
+/// Modification d'un élément, reçue par le canal retourné par `CacheDB::watch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheEvent<K, V> {
+    /// L'élément a été inséré ou mis à jour.
+    Inserted { key: K, value: V },
+    /// L'élément a été supprimé explicitement.
+    Removed { key: K, value: V },
+    /// L'élément a été évincé pour respecter la capacité ou le budget mémoire du cache.
+    Evicted { key: K, value: V },
+    /// La durée de vie stricte de l'élément est écoulée.
+    Expired { key: K, value: V },
+    /// La valeur de l'élément ne correspondait plus à sa somme de contrôle.
+    Corrupted { key: K, value: V },
+}
+
+/// Observateur qui transmet dans un canal les modifications d'une seule clé.
+pub(crate) struct KeyWatcher<K, V> {
+    key: K,
+    sender: Sender<CacheEvent<K, V>>,
+    disconnected: Cell<bool>,
+}
+
+impl<K: PartialEq + Clone, V: Clone> KeyWatcher<K, V> {
+    pub(crate) fn new(key: K, sender: Sender<CacheEvent<K, V>>) -> Self {
+        KeyWatcher { key, sender, disconnected: Cell::new(false) }
+    }
+
+    /// Transmet un évènement s'il concerne la clé observée.
+    ///
+    /// Si le récepteur a été fermé, l'observateur est marqué comme déconnecté et n'envoie plus rien.
+    fn send<F>(&self, key: &K, value: &V, event: F)
+    where
+        F: FnOnce(K, V) -> CacheEvent<K, V>,
+    {
+        if *key == self.key && !self.disconnected.get() && self.sender.send(event(key.clone(), value.clone())).is_err() {
+            self.disconnected.set(true);
+        }
+    }
+}
+
+impl<K: PartialEq + Clone, V: Clone> CacheEventListener<K, V> for KeyWatcher<K, V> {
+    fn on_insert(&self, key: &K, value: &V) {
+        self.send(key, value, |key, value| CacheEvent::Inserted { key, value });
+    }
+
+    fn on_remove(&self, key: &K, value: &V) {
+        self.send(key, value, |key, value| CacheEvent::Removed { key, value });
+    }
+
+    fn on_evict(&self, key: &K, value: &V) {
+        self.send(key, value, |key, value| CacheEvent::Evicted { key, value });
+    }
+
+    fn on_expire(&self, key: &K, value: &V) {
+        self.send(key, value, |key, value| CacheEvent::Expired { key, value });
+    }
+
+    fn on_corrupted(&self, key: &K, value: &V) {
+        self.send(key, value, |key, value| CacheEvent::Corrupted { key, value });
+    }
+
+    fn is_closed(&self) -> bool {
+        self.disconnected.get()
+    }
+}
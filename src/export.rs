@@ -0,0 +1,61 @@
+use crate::errors::CustomError;
+use serde::{Deserialize, Serialize};
+
+/// Format utilisé pour exporter ou importer le contenu d'un cache.
+///
+/// * `Json` - Un tableau d'objets `{"key": ..., "value": ...}`.
+/// * `Csv` - Une ligne d'en-tête `key,value` suivie d'une ligne par élément ; chaque champ
+///   contient la clé ou la valeur encodée en JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Élément tel qu'il apparaît dans un export JSON.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExportEntry<K, V> {
+    pub(crate) key: K,
+    pub(crate) value: V,
+}
+
+pub(crate) const CSV_HEADER: &str = "key,value";
+
+/// Encode un champ CSV en l'entourant de guillemets si nécessaire.
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains(['"', ',', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Découpe une ligne CSV en champs, en tenant compte des guillemets.
+pub(crate) fn parse_csv_line(line: &str) -> Result<Vec<String>, CustomError> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(CustomError::BadRequest);
+    }
+    fields.push(field);
+    Ok(fields)
+}
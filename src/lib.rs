@@ -1,7 +1,48 @@
-mod errors;
+mod backing;
+mod batch;
+mod capacity;
+mod checksum;
+mod entry;
+pub mod errors;
+mod events;
+mod export;
+mod lock;
+mod lru;
+mod migrations;
+mod oplog;
+mod patch;
 mod persistent;
-mod utils;
+mod record;
+mod recovery;
+pub mod resp;
+mod shadow;
+mod single_flight;
+mod slowlog;
+mod stats;
+mod tiered;
+mod transaction;
+mod typed;
+pub mod utils;
+mod write_behind;
 
+pub use backing::{BackingStore, FnBackingStore};
+pub use batch::{BatchItem, BatchStatus};
+pub use capacity::CapacityPolicy;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
 pub use errors::CustomError;
+pub use events::{CacheEvent, CacheEventListener};
+pub use export::ExportFormat;
+pub use lock::LockMode;
+pub use migrations::MigrationRecord;
+pub use oplog::Operation;
 pub use persistent::CacheDB;
+pub use record::{EntryMetadata, IntoIter, Iter, TtlInfo, ValueVersion};
+pub use recovery::{RecoveryReport, SkippedLine};
+pub use shadow::{DivergenceReport, ShadowWriter};
+pub use single_flight::SingleFlight;
+pub use slowlog::SlowOperation;
+pub use stats::{CacheStats, TieredStats};
+pub use tiered::TieredCache;
+pub use transaction::Transaction;
+pub use typed::TypedCache;
 
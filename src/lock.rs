@@ -0,0 +1,41 @@
+use crate::errors::CustomError;
+use std::fs::{File, OpenOptions, TryLockError};
+
+/// Mode de verrouillage du fichier d'un cache, choisi avec `CacheDB::with_file_lock`.
+///
+/// * `Exclusive` - Un seul processus peut ouvrir le fichier ; le cache est utilisable normalement.
+/// * `Shared` - Plusieurs processus peuvent ouvrir le fichier en lecture, mais aucun en mode
+///   exclusif ; le cache est en lecture seule et toute sauvegarde échoue avec
+///   `CustomError::CacheDbLockError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    Exclusive,
+    Shared,
+}
+
+/// Verrou consultatif posé sur le fichier d'un cache, libéré à sa destruction.
+#[derive(Debug)]
+pub(crate) struct FileLock {
+    // Le verrou est libéré à la fermeture du fichier.
+    _file: File,
+    pub(crate) mode: LockMode,
+}
+
+impl FileLock {
+    /// Verrouille un fichier, en le créant s'il n'existe pas.
+    ///
+    /// Retourne `CustomError::CacheDbLockError` si un autre processus détient un verrou incompatible.
+    pub(crate) fn acquire(path: &str, mode: LockMode) -> Result<Self, CustomError> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+
+        let result = match mode {
+            LockMode::Exclusive => file.try_lock(),
+            LockMode::Shared => file.try_lock_shared(),
+        };
+        match result {
+            Ok(()) => Ok(FileLock { _file: file, mode }),
+            Err(TryLockError::WouldBlock) => Err(CustomError::CacheDbLockError),
+            Err(TryLockError::Error(e)) => Err(CustomError::IoError(e)),
+        }
+    }
+}
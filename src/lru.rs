@@ -0,0 +1,202 @@
+use crate::record::Record;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Index, IndexMut};
+
+/// Liste des éléments d'un cache, du moins au plus récemment utilisé, indexée par clé.
+///
+/// Les éléments sont stockés dans des emplacements dont l'indice ne change pas tant que l'élément
+/// n'est pas retiré, et chaînés dans l'ordre d'utilisation. La recherche d'une clé, le retrait
+/// d'un élément et son déplacement en dernière position se font en temps constant.
+#[derive(Clone)]
+pub(crate) struct LruList<K, V> {
+    slots: Vec<Slot<K, V>>,
+    free: Vec<usize>,
+    index: HashMap<K, usize>,
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+#[derive(Clone)]
+struct Slot<K, V> {
+    record: Option<Record<K, V>>,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl<K: Eq + Hash + Clone, V> LruList<K, V> {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        LruList {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            index: HashMap::with_capacity(capacity),
+            head: None,
+            tail: None,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Retourne l'emplacement de l'élément associé à une clé.
+    pub(crate) fn position(&self, key: &K) -> Option<usize> {
+        self.index.get(key).copied()
+    }
+
+    /// Retourne l'élément le plus récemment utilisé.
+    pub(crate) fn last(&self) -> Option<&Record<K, V>> {
+        self.tail.map(|slot| &self[slot])
+    }
+
+    /// Retourne l'élément le plus récemment utilisé.
+    pub(crate) fn last_mut(&mut self) -> Option<&mut Record<K, V>> {
+        self.tail.map(|slot| &mut self[slot])
+    }
+
+    /// Ajoute un élément en dernière position et retourne son emplacement.
+    ///
+    /// La clé ne doit pas déjà être dans la liste.
+    pub(crate) fn push(&mut self, record: Record<K, V>) -> usize {
+        let key = record.key.clone();
+        let slot = Slot { record: Some(record), prev: None, next: None };
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index] = slot;
+                index
+            }
+            None => {
+                self.slots.push(slot);
+                self.slots.len() - 1
+            }
+        };
+        self.index.insert(key, index);
+        self.link_back(index);
+        index
+    }
+
+    /// Retire l'élément de l'emplacement donné.
+    pub(crate) fn remove(&mut self, index: usize) -> Record<K, V> {
+        self.unlink(index);
+        let record = self.slots[index].record.take().expect("emplacement vide");
+        self.index.remove(&record.key);
+        self.free.push(index);
+        record
+    }
+
+    /// Retire l'élément le moins récemment utilisé.
+    pub(crate) fn pop_front(&mut self) -> Option<Record<K, V>> {
+        self.head.map(|index| self.remove(index))
+    }
+
+    /// Retire l'élément le plus récemment utilisé.
+    pub(crate) fn pop_back(&mut self) -> Option<Record<K, V>> {
+        self.tail.map(|index| self.remove(index))
+    }
+
+    /// Déplace l'élément de l'emplacement donné en dernière position.
+    pub(crate) fn move_to_back(&mut self, index: usize) {
+        if self.tail != Some(index) {
+            self.unlink(index);
+            self.link_back(index);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.slots.clear();
+        self.free.clear();
+        self.index.clear();
+        self.head = None;
+        self.tail = None;
+    }
+
+    /// Retourne un itérateur sur les éléments, du moins au plus récemment utilisé.
+    pub(crate) fn iter(&self) -> Records<'_, K, V> {
+        Records {
+            slots: &self.slots,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len(),
+        }
+    }
+
+    fn link_back(&mut self, index: usize) {
+        self.slots[index].prev = self.tail;
+        self.slots[index].next = None;
+        match self.tail {
+            Some(tail) => self.slots[tail].next = Some(index),
+            None => self.head = Some(index),
+        }
+        self.tail = Some(index);
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let (prev, next) = (self.slots[index].prev, self.slots[index].next);
+        match prev {
+            Some(prev) => self.slots[prev].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.slots[next].prev = prev,
+            None => self.tail = prev,
+        }
+    }
+}
+
+impl<K, V> Index<usize> for LruList<K, V> {
+    type Output = Record<K, V>;
+
+    fn index(&self, index: usize) -> &Record<K, V> {
+        self.slots[index].record.as_ref().expect("emplacement vide")
+    }
+}
+
+impl<K, V> IndexMut<usize> for LruList<K, V> {
+    fn index_mut(&mut self, index: usize) -> &mut Record<K, V> {
+        self.slots[index].record.as_mut().expect("emplacement vide")
+    }
+}
+
+/// Itérateur sur les éléments d'une `LruList`, du moins au plus récemment utilisé.
+pub(crate) struct Records<'a, K, V> {
+    slots: &'a [Slot<K, V>],
+    front: Option<usize>,
+    back: Option<usize>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Records<'a, K, V> {
+    type Item = &'a Record<K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let slot = &self.slots[self.front?];
+        self.front = slot.next;
+        self.remaining -= 1;
+        slot.record.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Records<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let slot = &self.slots[self.back?];
+        self.back = slot.prev;
+        self.remaining -= 1;
+        slot.record.as_ref()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Records<'_, K, V> {}
@@ -1,35 +1,118 @@
-mod errors;
-mod persistent;
-mod utils;
+use eval_rust::{CacheDB, CustomError};
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
 
-use persistent::CacheDB;
-use crate::errors::CustomError;
+const HELP: &str = "Commandes:
+  put <clé> <valeur>   Insère une valeur
+  get <clé>            Affiche la valeur associée à la clé
+  peek <clé>           Affiche la valeur sans la marquer comme récemment utilisée
+  rm <clé>             Supprime la clé
+  ttl <clé> <secondes> Définit la durée de vie de la clé
+  ls                   Liste les éléments, du moins au plus récemment utilisé
+  stats                Affiche les statistiques du cache
+  resize <capacité>    Modifie la capacité du cache
+  clear                Vide le cache
+  help                 Affiche cette aide
+  quit                 Quitte le shell";
 
-fn main() -> Result<(), CustomError>{
-
-    let mut cache = match CacheDB::new_persistent(3, "cache.txt") {
-        Ok(cache) => cache,
-        Err(e) => return Err(e),
+fn main() -> Result<(), CustomError> {
+    let mut args = std::env::args().skip(1);
+    let file_path = args.next().unwrap_or_else(|| "cache.txt".to_string());
+    let capacity = match args.next() {
+        Some(capacity) => capacity.parse().map_err(|_| CustomError::BadRequest)?,
+        None => 1000,
     };
 
-    if let Err(e) = cache.put("1".to_string(), "rouge".to_string()) {
-        return Err(e);
-    }
-    if let Err(e) = cache.put("banane".to_string(), "jaune".to_string()) {
-        return Err(e);
-    }
+    let mut cache = CacheDB::new_persistent(capacity, &file_path)?;
+    println!("Cache {} ouvert ({} éléments). Tapez `help` pour la liste des commandes.", file_path, cache.len());
 
-    println!("Valeur pour 1: {:?}", cache.get(&"1".to_string()));
-    println!("Valeur pour banane: {:?}", cache.get(&"banane".to_string()));
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
 
-    if let Err(e) = cache.remove(&"1".to_string()) {
-        return Err(e);
-    }
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
 
-    for (key, value) in cache.iter() {
-        println!("{} = {}", key, value);
-    }
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
 
+        match execute(&mut cache, line.trim()) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => println!("Erreur: {}", e),
+        }
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Exécute une commande du shell.
+///
+/// Retourne `Ok(false)` lorsque l'utilisateur demande à quitter.
+fn execute(cache: &mut CacheDB<String, String>, line: &str) -> Result<bool, CustomError> {
+    let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let rest = rest.trim();
+
+    match command {
+        "" => {}
+        "put" => match rest.split_once(' ') {
+            Some((key, value)) => {
+                cache.put(key.to_string(), value.trim().to_string())?;
+            }
+            None => return Err(CustomError::BadRequest),
+        },
+        "get" if !rest.is_empty() => match cache.get(&rest.to_string()) {
+            Some(value) => println!("{}", value),
+            None => return Err(CustomError::NotFound),
+        },
+        "peek" if !rest.is_empty() => match cache.peek(&rest.to_string()) {
+            Some(value) => println!("{}", value),
+            None => return Err(CustomError::NotFound),
+        },
+        "ttl" => match rest.split_once(' ') {
+            Some((key, seconds)) => {
+                let seconds = seconds.trim().parse().map_err(|_| CustomError::BadRequest)?;
+                cache.expire(&key.to_string(), None, Some(Duration::from_secs(seconds)))?;
+            }
+            None => return Err(CustomError::BadRequest),
+        },
+        "rm" if !rest.is_empty() => {
+            cache.remove(&rest.to_string())?;
+        }
+        "ls" => {
+            for (key, value) in cache.iter() {
+                println!("{} = {}", key, value);
+            }
+        }
+        "stats" => {
+            let stats = cache.stats();
+            println!("éléments: {}/{}", stats.len, stats.capacity);
+            println!("mémoire utilisée: {} octets", stats.used_bytes);
+            match stats.hit_ratio() {
+                Some(ratio) => println!("lectures: {} succès, {} échecs ({:.1} %)", stats.hits, stats.misses, ratio * 100.0),
+                None => println!("lectures: aucune"),
+            }
+            println!("évictions: {}", stats.evictions);
+            println!("ouvert depuis: {:?}", stats.uptime);
+            if let (Some(duration), Some(file_size)) = (stats.last_persist_duration, stats.file_size) {
+                println!("dernière sauvegarde: {:?} ({} octets)", duration, file_size);
+            }
+        }
+        "resize" if !rest.is_empty() => {
+            let capacity = rest.parse().map_err(|_| CustomError::BadRequest)?;
+            let evicted = cache.resize(capacity)?;
+            println!("capacité: {} ({} éléments évincés)", cache.capacity(), evicted);
+        }
+        "clear" => {
+            let count = cache.clear()?;
+            println!("{} éléments supprimés", count);
+        }
+        "help" => println!("{}", HELP),
+        "quit" | "exit" => return Ok(false),
+        _ => return Err(CustomError::BadRequest),
+    }
+
+    Ok(true)
+}
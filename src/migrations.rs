@@ -0,0 +1,94 @@
+use crate::errors::CustomError;
+use crate::record::{split_line, EntryMetadata};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// Préfixe des lignes d'en-tête enregistrant les migrations appliquées à un fichier de cache.
+pub(crate) const HISTORY_PREFIX: &str = "#migration=";
+
+/// Migration appliquée à un fichier de cache, telle qu'enregistrée dans son historique.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    /// Le numéro de version de la migration.
+    pub version: u32,
+    /// Le nom de la migration.
+    pub name: String,
+    /// La date à laquelle la migration a été appliquée.
+    pub applied_at: SystemTime,
+}
+
+/// Étape de migration des lignes d'un fichier de cache vers une nouvelle version du format.
+///
+/// Chaque ligne d'élément est migrée indépendamment des autres.
+pub(crate) struct Migration {
+    pub(crate) version: u32,
+    pub(crate) name: &'static str,
+    apply: fn(String) -> Result<String, CustomError>,
+}
+
+/// Version de la migration à partir de laquelle chaque ligne d'élément doit porter sa somme de contrôle.
+pub(crate) const LINE_CHECKSUMS_VERSION: u32 = 2;
+
+/// Migrations connues, triées par version croissante.
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "add_entry_metadata",
+        apply: add_entry_metadata,
+    },
+    Migration {
+        version: LINE_CHECKSUMS_VERSION,
+        name: "add_line_checksums",
+        apply: add_line_checksums,
+    },
+];
+
+/// Ajoute des métadonnées initialisées à la date de la migration aux lignes `clé=valeur`.
+fn add_entry_metadata(line: String) -> Result<String, CustomError> {
+    match split_line(&line).len() {
+        2 => Ok(format!("{}={}", line, serde_json::to_string(&EntryMetadata::new())?)),
+        3 => Ok(line),
+        _ => Err(CustomError::CacheDbLoadError),
+    }
+}
+
+/// Marque le fichier comme portant une somme de contrôle par ligne.
+///
+/// Les lignes ne sont pas modifiées : leurs sommes de contrôle sont ajoutées à la prochaine sauvegarde,
+/// après quoi une ligne sans somme de contrôle est considérée comme corrompue.
+fn add_line_checksums(line: String) -> Result<String, CustomError> {
+    Ok(line)
+}
+
+/// Retourne l'historique d'un fichier créé directement dans la version courante du format.
+pub(crate) fn current_history() -> Vec<MigrationRecord> {
+    let now = SystemTime::now();
+    MIGRATIONS
+        .iter()
+        .map(|migration| MigrationRecord {
+            version: migration.version,
+            name: migration.name.to_string(),
+            applied_at: now,
+        })
+        .collect()
+}
+
+/// Applique aux lignes d'un fichier les migrations absentes de son historique, dans l'ordre des versions.
+///
+/// Les migrations appliquées sont ajoutées à l'historique. Retourne le résultat de la migration
+/// de chaque ligne, dans l'ordre des lignes reçues.
+pub(crate) fn migrate(lines: Vec<String>, history: &mut Vec<MigrationRecord>) -> Vec<Result<String, CustomError>> {
+    let applied = history.iter().map(|record| record.version).max().unwrap_or(0);
+    let mut lines: Vec<Result<String, CustomError>> = lines.into_iter().map(Ok).collect();
+
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > applied) {
+        lines = lines.into_iter().map(|line| line.and_then(migration.apply)).collect();
+        history.push(MigrationRecord {
+            version: migration.version,
+            name: migration.name.to_string(),
+            applied_at: SystemTime::now(),
+        });
+    }
+
+    lines
+}
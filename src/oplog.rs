@@ -0,0 +1,48 @@
+use crate::errors::CustomError;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::time::Duration;
+
+/// Opération de modification d'un cache, telle qu'enregistrée dans un journal d'opérations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation<K, V> {
+    Put { key: K, value: V },
+    ApplyPatch { key: K, patch: serde_json::Value },
+    Remove { key: K },
+    Restore { key: K },
+    Clear,
+    Freeze { key: K },
+    Unfreeze { key: K },
+    Pin { key: K },
+    Unpin { key: K },
+    Touch { key: K },
+    Expire { key: K, soft_ttl: Option<Duration>, hard_ttl: Option<Duration> },
+}
+
+/// Journal dans lequel les opérations d'un cache sont ajoutées, une ligne JSON par opération.
+#[derive(Debug)]
+pub(crate) struct OperationLog {
+    file: File,
+}
+
+impl OperationLog {
+    pub(crate) fn open(path: &str) -> Result<Self, CustomError> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(OperationLog { file })
+    }
+
+    /// Ajoute une opération à la fin du journal.
+    pub(crate) fn append<K: Serialize, V: Serialize>(&mut self, operation: &Operation<K, V>) -> Result<(), CustomError> {
+        let line = serde_json::to_string(operation)?;
+        writeln!(self.file, "{}", line)?;
+        Ok(())
+    }
+
+    /// Attend que les opérations ajoutées soient écrites sur le disque.
+    pub(crate) fn sync(&mut self) -> Result<(), CustomError> {
+        self.file.sync_all()?;
+        Ok(())
+    }
+}
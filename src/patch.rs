@@ -0,0 +1,26 @@
+use serde_json::Value;
+
+/// Applique un JSON Merge Patch (RFC 7396) à un document JSON.
+///
+/// Les membres du patch valant `null` sont supprimés du document, les objets sont fusionnés
+/// récursivement et toute autre valeur remplace celle du document.
+pub(crate) fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch_members) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+
+    if let Value::Object(target_members) = target {
+        for (name, value) in patch_members {
+            if value.is_null() {
+                target_members.remove(name);
+            } else {
+                merge_patch(target_members.entry(name.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
@@ -1,9 +1,35 @@
+use crate::backing::BackingStore;
+use crate::batch::{BatchItem, BatchStatus};
+use crate::capacity::CapacityPolicy;
+use crate::checksum::{append_line_checksum, strip_line_checksum, value_checksum};
+use crate::entry::{Entry, OccupiedEntry, VacantEntry};
 use crate::errors::CustomError;
+use crate::stats::{AccessCounters, CacheStats, PersistMetrics};
+use crate::migrations::{self, MigrationRecord, HISTORY_PREFIX, LINE_CHECKSUMS_VERSION};
+use crate::oplog::{Operation, OperationLog};
+use crate::patch::merge_patch;
+use crate::record::{parse_metadata, record_metadata_to_json, split_line, take_history, EntryMetadata, Extensions, IntoIter, Iter, Record, TtlInfo, ValueVersion};
+use crate::recovery::{RecoveryReport, SkippedLine};
+use crate::events::{CacheEvent, CacheEventListener, KeyWatcher};
+use crate::lock::{FileLock, LockMode};
+use crate::lru::LruList;
+use crate::export::{csv_field, parse_csv_line, ExportEntry, ExportFormat, CSV_HEADER};
+use crate::write_behind::WriteBehind;
+use crate::transaction::{Transaction, TransactionOperation};
+use crate::slowlog::{SlowLog, SlowOperation};
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, BufWriter, Write, Read};
 use serde::{Serialize, Deserialize};
 use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::{self, JoinHandle};
 use serde::de::Error;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::time::{Duration, Instant, SystemTime};
 
 /// Cache qui stock les données dans un fichier.
 ///
@@ -37,22 +63,43 @@ use serde::de::Error;
 ///
 /// // Vide le cache.
 /// cache.clear()?;
+/// # std::fs::remove_file("cache.txt")?;
 /// # Ok(())
 /// # }
 /// ```
 pub struct CacheDB<K, V>
 where
-    K: Eq + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
     V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
 {
-    cache: Vec<(K, V)>,
+    cache: LruList<K, V>,
     capacity: usize,
+    capacity_policy: CapacityPolicy,
     file_path: String,
+    write_behind: Option<WriteBehind>,
+    max_bytes: Option<usize>,
+    used_bytes: usize,
+    migration_history: Vec<MigrationRecord>,
+    extra_headers: Vec<String>,
+    listeners: Vec<Box<dyn CacheEventListener<K, V> + Send>>,
+    operation_log: Option<OperationLog>,
+    backing_store: Option<Box<dyn BackingStore<K, V> + Send>>,
+    file_lock: Option<FileLock>,
+    persist_metrics: Cell<PersistMetrics>,
+    access_counters: AccessCounters,
+    opened_at: Instant,
+    slowlog: Option<RefCell<SlowLog>>,
+    soft_delete: Option<Duration>,
+    history_depth: usize,
+    tombstones: Vec<Record<K, V>>,
+    lenient: bool,
+    recovery_report: Option<RecoveryReport>,
+    redact_debug: bool,
 }
 
 impl<K, V> CacheDB<K, V>
 where
-    K: Eq + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
     V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
 {
     /// Crée un nouveau cache avec la capacité et le chemin du fichier spécifiés.
@@ -75,18 +122,118 @@ where
     /// use eval_rust::errors::CustomError;
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let cache = CacheDB::<String, String>::new_persistent(10, "cache.txt")?;
+    /// let cache = CacheDB::<String, String>::new_persistent(10, "cache_new_persistent.txt")?;
     /// # Ok(())
     /// # }
     /// ```
     pub fn new_persistent(capacity: usize, file_path: &str) -> Result<Self, CustomError> {
+        CacheDB::new_persistent_with_policy(capacity, file_path, CapacityPolicy::default())
+    }
+
+    /// Crée un nouveau cache en précisant la politique appliquée si le fichier contient plus
+    /// d'éléments que la capacité, par exemple après une réduction de la capacité.
+    ///
+    /// La politique s'applique aussi aux chargements ultérieurs avec `load` et `restore_from`.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - La capacité maximale du cache.
+    /// * `file_path` - Le chemin du fichier où le cache sera stocké.
+    /// * `capacity_policy` - La politique appliquée aux fichiers trop grands.
+    ///
+    /// # Retour
+    ///
+    /// Retourne un `Result`, `CustomError::CacheDbCapacityError` si le fichier est trop grand avec
+    /// `CapacityPolicy::Error`, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::{CacheDB, CapacityPolicy};
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_new_persistent_with_policy.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.put("banane".to_string(), 2)?;
+    /// drop(cache);
+    ///
+    /// let result = CacheDB::<String, i32>::new_persistent_with_policy(1, "cache_new_persistent_with_policy.txt", CapacityPolicy::Error);
+    /// assert!(matches!(result, Err(CustomError::CacheDbCapacityError)));
+    ///
+    /// let cache = CacheDB::<String, i32>::new_persistent_with_policy(1, "cache_new_persistent_with_policy.txt", CapacityPolicy::Grow)?;
+    /// assert_eq!(cache.stats().capacity, 2);
+    /// # std::fs::remove_file("cache_new_persistent_with_policy.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_persistent_with_policy(capacity: usize, file_path: &str, capacity_policy: CapacityPolicy) -> Result<Self, CustomError> {
+        CacheDB::new_persistent_with_options(capacity, file_path, capacity_policy, false)
+    }
+
+    /// Crée un nouveau cache en mode tolérant : les lignes illisibles ou corrompues du fichier
+    /// sont ignorées au lieu de faire échouer le chargement.
+    ///
+    /// Le mode s'applique aussi aux chargements ultérieurs avec `load`. Les lignes ignorées sont
+    /// décrites par `recovery_report`.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - La capacité maximale du cache.
+    /// * `file_path` - Le chemin du fichier où le cache sera stocké.
+    ///
+    /// # Retour
+    ///
+    /// Retourne un `Result` ou une erreur `CustomError` si le fichier n'a pas pu être lu.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// std::fs::write("cache_new_persistent_lenient.txt", "\"pomme\"=1\nillisible\n")?;
+    ///
+    /// let cache = CacheDB::<String, i32>::new_persistent_lenient(5, "cache_new_persistent_lenient.txt")?;
+    /// assert_eq!(cache.len(), 1);
+    /// assert_eq!(cache.recovery_report().unwrap().skipped[0].line, 2);
+    /// # std::fs::remove_file("cache_new_persistent_lenient.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_persistent_lenient(capacity: usize, file_path: &str) -> Result<Self, CustomError> {
+        CacheDB::new_persistent_with_options(capacity, file_path, CapacityPolicy::default(), true)
+    }
+
+    fn new_persistent_with_options(capacity: usize, file_path: &str, capacity_policy: CapacityPolicy, lenient: bool) -> Result<Self, CustomError> {
         let file_path_clone = file_path.to_string();
-        let cache = Vec::with_capacity(capacity);
+        let cache = LruList::with_capacity(capacity);
 
         let mut persistent_cache = CacheDB {
             cache,
             capacity,
+            capacity_policy,
             file_path: file_path.to_string(),
+            write_behind: None,
+            max_bytes: None,
+            used_bytes: 0,
+            migration_history: migrations::current_history(),
+            extra_headers: Vec::new(),
+            listeners: Vec::new(),
+            operation_log: None,
+            backing_store: None,
+            file_lock: None,
+            persist_metrics: Cell::new(PersistMetrics::default()),
+            access_counters: AccessCounters::default(),
+            opened_at: Instant::now(),
+            slowlog: None,
+            soft_delete: None,
+            history_depth: 0,
+            tombstones: Vec::new(),
+            lenient,
+            recovery_report: None,
+            redact_debug: false,
         };
 
         if Path::new(&file_path_clone).exists() {
@@ -96,132 +243,202 @@ where
         Ok(persistent_cache)
     }
 
-    /// Sauvegarde le cache dans le fichier.
+    /// Active la persistance différée (write-behind).
     ///
-    /// # Retour
+    /// Les modifications ne sont plus écrites sur disque à chaque appel : le cache est sauvegardé
+    /// après `max_pending` modifications, ou lors de la première modification survenant après
+    /// `flush_interval` depuis la dernière sauvegarde. Les lectures reflètent toujours les
+    /// modifications en attente. Utilisez `flush()` pour forcer la sauvegarde ; les modifications
+    /// en attente sont également sauvegardées lorsque le cache est détruit. Pour qu'elles le soient
+    /// aussi lorsque le cache ne reçoit plus de modifications, lancez `spawn_flusher`.
     ///
-    /// Retourne `Ok(())` si le cache a été sauvegardé avec succès, ou une erreur `CustomError` si une erreur s'est produite.
+    /// # Arguments
+    ///
+    /// * `flush_interval` - Le délai maximal entre deux sauvegardes.
+    /// * `max_pending` - Le nombre de modifications au-delà duquel le cache est sauvegardé.
     ///
     /// # Exemples
     ///
     /// ```
     /// use eval_rust::CacheDB;
     /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache.txt")?;
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_with_write_behind.txt")?
+    ///     .with_write_behind(Duration::from_millis(500), 100);
     /// cache.put("pomme".to_string(), 1)?;
-    /// cache.save()?;
+    /// cache.flush()?;
+    /// # std::fs::remove_file("cache_with_write_behind.txt")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn save(&self) -> Result<(), CustomError> {
-        let file = match OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&self.file_path)
-        {
-            Ok(file) => file,
-            Err(_) => return Err(CustomError::CacheDbSaveError),
-        };
-
-        let mut writer = BufWriter::new(file);
+    pub fn with_write_behind(mut self, flush_interval: Duration, max_pending: usize) -> Self {
+        self.write_behind = Some(WriteBehind::new(flush_interval, max_pending));
+        self
+    }
 
-        for (key, value) in self.cache.iter() {
-            let key_json = match serde_json::to_string(key) {
-                Ok(json) => json,
-                Err(_) => return Err(CustomError::SerializationError(serde_json::Error::custom(
-                    "Failed to serialize key to JSON",
-                ))),
+    /// Lance un thread qui sauvegarde les modifications en attente de la persistance différée dès
+    /// que `flush_interval` est écoulé, sans attendre la modification suivante.
+    ///
+    /// Le thread vérifie le cache au plus toutes les 10 ms, même si `flush_interval` est nul. Il
+    /// s'arrête lorsque le cache n'est plus référencé ailleurs, ou immédiatement si la persistance
+    /// différée n'est pas activée.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - Le cache partagé à sauvegarder.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let cache = CacheDB::<String, i32>::new_persistent(5, "cache_spawn_flusher.txt")?
+    ///     .with_write_behind(Duration::from_millis(50), 100);
+    /// let cache = Arc::new(Mutex::new(cache));
+    /// let flusher = CacheDB::spawn_flusher(&cache);
+    ///
+    /// cache.lock().unwrap().put("pomme".to_string(), 1)?;
+    /// drop(cache);
+    /// flusher.join().unwrap();
+    /// # std::fs::remove_file("cache_spawn_flusher.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_flusher(cache: &Arc<Mutex<Self>>) -> JoinHandle<()>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        let cache: Weak<Mutex<Self>> = Arc::downgrade(cache);
+        thread::spawn(move || loop {
+            let shared = match cache.upgrade() {
+                Some(shared) => shared,
+                None => break,
             };
-            let value_json = match serde_json::to_string(value) {
-                Ok(json) => json,
-                Err(_) => return Err(CustomError::SerializationError(serde_json::Error::custom(
-                    "Failed to serialize value to JSON",
-                ))),
+            let mut cache = shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let due = match cache.write_behind.as_ref() {
+                Some(write_behind) => write_behind.is_due(),
+                None => break,
             };
-            let line = format!("{}={}\n", key_json, value_json);
-            if let Err(_) = writer.write_all(line.as_bytes()) {
-                return Err(CustomError::CacheDbSaveError);
+            if due {
+                let _ = cache.flush();
             }
-        }
-
-        if let Err(_) = writer.flush() {
-            return Err(CustomError::CacheDbSaveError);
-        }
+            let wait = cache.write_behind.as_ref().map(WriteBehind::time_until_due).unwrap_or_default();
+            drop(cache);
+            drop(shared);
+            thread::sleep(wait);
+        })
+    }
 
-        Ok(())
+    /// Masque les valeurs dans la représentation `Debug` du cache, pour ne pas les écrire dans les journaux.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, String>::new_persistent(5, "cache_with_redacted_debug.txt")?
+    ///     .with_redacted_debug();
+    /// cache.put("jeton".to_string(), "secret".to_string())?;
+    /// assert!(!format!("{:?}", cache).contains("secret"));
+    /// # std::fs::remove_file("cache_with_redacted_debug.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_redacted_debug(mut self) -> Self {
+        self.redact_debug = true;
+        self
     }
 
-    /// Charge le cache à partir du fichier.
+    /// Active le journal des opérations lentes, consultable avec `slowlog`.
     ///
-    /// # Retour
+    /// Les appels à `put`, `put_with_ttl`, `remove`, `clear` et `save` dont la durée atteint le seuil sont
+    /// enregistrés, sauvegarde du fichier comprise ; seules les dernières opérations sont conservées.
     ///
-    /// Retourne `Ok(())` si le cache a été chargé avec succès, ou une erreur `CustomError` si une erreur s'est produite.
+    /// # Arguments
+    ///
+    /// * `threshold` - La durée à partir de laquelle une opération est enregistrée.
+    /// * `max_entries` - Le nombre maximal d'opérations conservées.
     ///
     /// # Exemples
     ///
     /// ```
     /// use eval_rust::CacheDB;
     /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache.txt")?;
-    /// cache.load()?;
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_with_slowlog.txt")?
+    ///     .with_slowlog(Duration::from_millis(10), 128);
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// for operation in cache.slowlog() {
+    ///     println!("{} : {:?}", operation.name, operation.duration);
+    /// }
+    /// # std::fs::remove_file("cache_with_slowlog.txt")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn load(&mut self) -> Result<(), CustomError> {
-        let file = match File::open(&self.file_path) {
-            Ok(file) => file,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
-            Err(_) => return Err(CustomError::CacheDbLoadError),
-        };
-
-        let mut reader = BufReader::new(file);
-        let mut contents = String::new();
-        if let Err(_) = reader.read_to_string(&mut contents) {
-            return Err(CustomError::CacheDbLoadError);
-        }
-
-        self.cache.clear();
-
-        for line in contents.lines() {
-            let parts: Vec<&str> = line.split('=').collect();
-            if parts.len() == 2 {
-                let key: K = match serde_json::from_str(parts[0]) {
-                    Ok(key) => key,
-                    Err(_) => return Err(CustomError::CacheDbLoadError),
-                };
-                let value: V = match serde_json::from_str(parts[1]) {
-                    Ok(value) => value,
-                    Err(_) => return Err(CustomError::CacheDbLoadError),
-                };
-                if let Err(e) = self.put(key, value) {
-                    return Err(e)
-                }
-            } else {
-                return Err(CustomError::CacheDbLoadError);
-            }
-        }
-
-        Ok(())
+    pub fn with_slowlog(mut self, threshold: Duration, max_entries: usize) -> Self {
+        self.slowlog = Some(RefCell::new(SlowLog::new(threshold, max_entries)));
+        self
     }
 
-    /// Insère une paire clé-valeur dans le cache.
+    /// Active la suppression réversible : les éléments supprimés par `remove` ou `retain` sont
+    /// conservés pendant un délai de grâce, au cours duquel ils peuvent être rétablis avec `restore`.
     ///
-    /// Si la clé existe déjà, la valeur associée est mise à jour.
-    /// Si le cache est plein, l'élément le moins récemment utilisé est supprimé.
+    /// Les éléments supprimés ne sont plus visibles par `get`, `iter` ou `len`, et ne comptent pas
+    /// dans la capacité du cache. Ils sont persistés avec le cache, et définitivement supprimés par
+    /// `purge_tombstones` une fois le délai écoulé. Une nouvelle valeur insérée pour la même clé
+    /// remplace l'élément supprimé.
     ///
     /// # Arguments
     ///
-    /// * `key` - La clé à insérer.
-    /// * `value` - La valeur à associer à la clé.
+    /// * `grace_period` - La durée pendant laquelle un élément supprimé peut être rétabli.
     ///
-    /// # Retour
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_with_soft_delete.txt")?
+    ///     .with_soft_delete(Duration::from_secs(3600));
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// cache.remove(&"pomme".to_string())?;
+    /// assert_eq!(cache.get(&"pomme".to_string()), None);
+    ///
+    /// cache.restore(&"pomme".to_string())?;
+    /// assert_eq!(cache.get(&"pomme".to_string()), Some(&1));
+    /// # std::fs::remove_file("cache_with_soft_delete.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_soft_delete(mut self, grace_period: Duration) -> Self {
+        self.soft_delete = Some(grace_period);
+        self
+    }
+
+    /// Conserve les versions précédentes de la valeur de chaque élément, consultables avec `history`.
+    ///
+    /// À chaque modification d'un élément, la valeur remplacée est ajoutée à son historique, qui
+    /// est persisté avec l'élément ; seules les `depth` dernières versions sont conservées. Sans
+    /// appel à cette méthode, l'historique d'un élément est effacé lors de sa prochaine modification.
+    ///
+    /// # Arguments
     ///
-    /// Retourne `Ok(())` si l'insertion a réussi, ou une erreur `CustomError` si une erreur s'est produite.
+    /// * `depth` - Le nombre maximal de versions précédentes conservées par élément.
     ///
     /// # Exemples
     ///
@@ -230,41 +447,60 @@ where
     /// use eval_rust::errors::CustomError;
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache.txt")?;
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_with_history.txt")?
+    ///     .with_history(10);
     /// cache.put("pomme".to_string(), 1)?;
-    /// cache.put("banane".to_string(), 2)?;
+    /// cache.put("pomme".to_string(), 2)?;
+    ///
+    /// let history = cache.history(&"pomme".to_string());
+    /// assert_eq!((history[0].version, history[0].value), (1, 1));
+    ///
+    /// cache.rollback(&"pomme".to_string(), 1)?;
+    /// assert_eq!(cache.get(&"pomme".to_string()), Some(&1));
+    /// # std::fs::remove_file("cache_with_history.txt")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn put(&mut self, key: K, value: V) -> Result<(), CustomError> {
-        if let Some(index) = self.cache.iter().position(|(k, _)| k == &key) {
-            if let Some((_, val)) = self.cache.get_mut(index) {
-                *val = value;
-            }
-            let element = self.cache.remove(index);
-            self.cache.push(element);
-            return self.save();
-        }
+    pub fn with_history(mut self, depth: usize) -> Self {
+        self.history_depth = depth;
+        self
+    }
+
+    /// Retourne les dernières opérations lentes, de la plus ancienne à la plus récente.
+    ///
+    /// Retourne une liste vide si le journal n'a pas été activé avec `with_slowlog`.
+    pub fn slowlog(&self) -> Vec<SlowOperation> {
+        self.slowlog.as_ref().map(|slowlog| slowlog.borrow().entries()).unwrap_or_default()
+    }
 
-        if self.cache.len() >= self.capacity {
-            self.cache.remove(0);
+    /// Enregistre la durée d'une opération dans le journal des opérations lentes, s'il est activé.
+    fn record_slow(&self, name: &'static str, duration: Duration) {
+        if let Some(slowlog) = self.slowlog.as_ref() {
+            slowlog.borrow_mut().record(name, duration);
         }
+    }
 
-        self.cache.push((key, value));
-        self.save()
+    /// Exécute une opération en mesurant sa durée pour le journal des opérations lentes.
+    fn timed<T, F>(&mut self, name: &'static str, operation: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let started_at = Instant::now();
+        let result = operation(self);
+        self.record_slow(name, started_at.elapsed());
+        result
     }
 
-    /// Récupère la valeur associée à une clé dans le cache.
+    /// Limite la mémoire occupée par le cache à un nombre d'octets.
     ///
-    /// Si la clé est trouvée, la valeur correspondante est retournée et l'élément est marqué comme récemment utilisé.
+    /// La taille d'un élément est celle de sa clé et de sa valeur sérialisées en JSON.
+    /// Lorsque le budget est dépassé, les éléments les moins récemment utilisés sont supprimés,
+    /// en plus de la limite sur le nombre d'éléments. Un élément plus grand que le budget est
+    /// refusé avec `CustomError::CacheDbCapacityError`.
     ///
     /// # Arguments
     ///
-    /// * `key` - La clé à rechercher.
-    ///
-    /// # Retour
-    ///
-    /// Retourne `Some(&V)` contenant une référence à la valeur associée à la clé si elle est trouvée, ou `None` si la clé n'est pas dans le cache.
+    /// * `max_bytes` - La mémoire maximale occupée par les éléments du cache, en octets.
     ///
     /// # Exemples
     ///
@@ -273,106 +509,231 @@ where
     /// use eval_rust::errors::CustomError;
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache.txt")?;
-    /// cache.put("pomme".to_string(), 1)?;
+    /// let mut cache = CacheDB::<String, String>::new_persistent(100, "cache_with_max_bytes.txt")?
+    ///     .with_max_bytes(32);
+    /// cache.put("pomme".to_string(), "rouge".to_string())?;
+    /// cache.put("banane".to_string(), "jaune".to_string())?;
+    /// cache.put("orange".to_string(), "orange".to_string())?;
     ///
-    /// if let Some(valeur) = cache.get(&"pomme".to_string()) {
-    ///     println!("La valeur de pomme est: {}", valeur);
-    /// }
+    /// // "pomme" a été supprimé pour respecter le budget.
+    /// assert_eq!(cache.len(), 2);
+    /// assert!(cache.stats().used_bytes <= 32);
+    /// # std::fs::remove_file("cache_with_max_bytes.txt")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn get(&mut self, key: &K) -> Option<&V> {
-        if let Some(index) = self.cache.iter().position(|(k, _)| k == key) {
-            let element = self.cache.remove(index);
-            self.cache.push(element);
-            return Some(&self.cache.last().unwrap().1);
-        }
-        None
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        while self.used_bytes > max_bytes && self.evict_lru().is_ok() {}
+        self
     }
 
-    /// Supprime l'élément associé à une clé du cache.
+    /// Verrouille le fichier du cache pour empêcher d'autres processus de le modifier.
+    ///
+    /// Le verrou est consultatif : il ne protège que des processus qui verrouillent aussi le fichier.
+    /// Une fois le verrou obtenu, le cache est rechargé depuis le fichier afin de refléter son état
+    /// au moment du verrouillage ; cette méthode doit donc être appelée juste après la construction.
+    /// Le verrou est libéré à la destruction du cache.
     ///
     /// # Arguments
     ///
-    /// * `key` - La clé à supprimer.
+    /// * `mode` - Le mode de verrouillage.
     ///
     /// # Retour
     ///
-    /// Retourne `Ok(())` si la clé a été trouvée et supprimée, ou une erreur `CustomError` si une erreur s'est produite.
+    /// Retourne le cache, `CustomError::CacheDbLockError` si un autre processus détient un verrou
+    /// incompatible, ou une autre erreur `CustomError` si une erreur s'est produite.
     ///
     /// # Exemples
     ///
     /// ```
-    /// use eval_rust::CacheDB;
+    /// use eval_rust::{CacheDB, LockMode};
     /// use eval_rust::errors::CustomError;
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache.txt")?;
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_with_file_lock.txt")?
+    ///     .with_file_lock(LockMode::Exclusive)?;
     /// cache.put("pomme".to_string(), 1)?;
-    /// cache.remove(&"pomme".to_string())?;
+    /// # drop(cache);
+    /// # std::fs::remove_file("cache_with_file_lock.txt")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn remove(&mut self, key: &K) -> Result<(), CustomError> {
-        if let Some(index) = self.cache.iter().position(|(k, _)| k == key){
-            self.cache.remove(index);
-            self.save()?;
-            Ok(())
-        } else {
-            Err(CustomError::NotFound)
+    pub fn with_file_lock(mut self, mode: LockMode) -> Result<Self, CustomError> {
+        self.file_lock = Some(FileLock::acquire(&self.file_path, mode)?);
+        self.load()?;
+        Ok(self)
+    }
+
+    /// Indique si le cache est en lecture seule, parce que son fichier est verrouillé en mode partagé.
+    fn is_read_only(&self) -> bool {
+        self.file_lock.as_ref().is_some_and(|lock| lock.mode == LockMode::Shared)
+    }
+
+    /// Retourne `CustomError::CacheDbLockError` si le cache est en lecture seule.
+    ///
+    /// Appelée au début de chaque modification, avant tout effet de bord (source de données,
+    /// mémoire, écouteurs, journal d'opérations), afin que le cache ne diverge pas de son fichier.
+    fn ensure_writable(&self) -> Result<(), CustomError> {
+        if self.is_read_only() {
+            return Err(CustomError::CacheDbLockError);
         }
+        Ok(())
     }
 
-    /// Vide le cache.
+    /// Place le cache devant une source de données, en lecture et en écriture directes.
     ///
-    /// # Retour
+    /// Une clé absente du cache lue avec `get` est recherchée dans la source puis insérée dans le
+    /// cache. Chaque valeur insérée avec `put`, `put_with_ttl`, `apply_patch` ou un import est
+    /// d'abord écrite dans la source ; si l'écriture échoue, l'erreur est retournée et le cache
+    /// n'est pas modifié.
     ///
-    /// Retourne `Ok(())` si le cache a été vidé avec succès, ou une erreur `CustomError` si une erreur s'est produite.
+    /// # Arguments
+    ///
+    /// * `store` - La source de données.
     ///
     /// # Exemples
     ///
     /// ```
-    /// use eval_rust::CacheDB;
+    /// use eval_rust::{CacheDB, FnBackingStore};
     /// use eval_rust::errors::CustomError;
+    /// use std::collections::HashMap;
+    /// use std::sync::{Arc, Mutex};
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache.txt")?;
+    /// let database = Arc::new(Mutex::new(HashMap::new()));
+    /// let (reader, writer) = (database.clone(), database.clone());
+    /// let store = FnBackingStore::new(
+    ///     move |key: &String| Ok(reader.lock().unwrap().get(key).cloned()),
+    ///     move |key: &String, value: &i32| {
+    ///         writer.lock().unwrap().insert(key.clone(), *value);
+    ///         Ok(())
+    ///     },
+    /// );
+    ///
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_with_backing_store.txt")?
+    ///     .with_backing_store(store);
     /// cache.put("pomme".to_string(), 1)?;
-    /// cache.clear()?;
-    /// assert_eq!(cache.len(), 0);
+    /// assert_eq!(database.lock().unwrap().get("pomme"), Some(&1));
+    /// # std::fs::remove_file("cache_with_backing_store.txt")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn clear(&mut self) -> Result<(), CustomError> {
-        self.cache.clear();
-        self.save()
+    pub fn with_backing_store<S>(mut self, store: S) -> Self
+    where
+        S: BackingStore<K, V> + Send + 'static,
+    {
+        self.backing_store = Some(Box::new(store));
+        self
     }
 
-    /// Retourne un itérateur sur les éléments du cache.
+    /// Enregistre un observateur notifié des insertions, suppressions et évictions d'éléments.
+    ///
+    /// Les observateurs sont appelés dans leur ordre d'enregistrement, après la modification en
+    /// mémoire et avant la persistance du cache. Le chargement et `restore_from` ne déclenchent
+    /// pas d'insertions.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - L'observateur à enregistrer.
     ///
     /// # Exemples
     ///
     /// ```
-    /// use eval_rust::CacheDB;
+    /// use eval_rust::{CacheDB, CacheEventListener};
+    /// use eval_rust::errors::CustomError;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// struct InsertCounter(Arc<AtomicUsize>);
+    ///
+    /// impl CacheEventListener<String, i32> for InsertCounter {
+    ///     fn on_insert(&self, _key: &String, _value: &i32) {
+    ///         self.0.fetch_add(1, Ordering::SeqCst);
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let inserts = Arc::new(AtomicUsize::new(0));
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_subscribe.txt")?;
+    /// cache.subscribe(InsertCounter(inserts.clone()));
+    ///
+    /// cache.put("pomme".to_string(), 1)?;
+    /// assert_eq!(inserts.load(Ordering::SeqCst), 1);
+    /// # std::fs::remove_file("cache_subscribe.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe<L>(&mut self, listener: L)
+    where
+        L: CacheEventListener<K, V> + Send + 'static,
+    {
+        self.prune_listeners();
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Retire les observateurs qui ne reçoivent plus d'évènements, comme ceux de `watch` dont le récepteur a été fermé.
+    fn prune_listeners(&mut self) {
+        self.listeners.retain(|listener| !listener.is_closed());
+    }
+
+    /// Observe les modifications d'une seule clé.
+    ///
+    /// Chaque insertion, mise à jour, suppression, éviction ou expiration de la clé est envoyée dans
+    /// le canal retourné, dans l'ordre où elle se produit. Le canal est alimenté depuis le thread qui
+    /// modifie le cache : il peut être lu depuis un autre thread, par exemple pour invalider une
+    /// copie locale. Une fois le récepteur fermé, l'observateur est retiré du cache après le
+    /// prochain évènement de la clé.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à observer.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::{CacheDB, CacheEvent};
     /// use eval_rust::errors::CustomError;
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache.txt")?;
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_watch.txt")?;
+    /// let events = cache.watch("pomme".to_string());
+    ///
     /// cache.put("pomme".to_string(), 1)?;
     /// cache.put("banane".to_string(), 2)?;
+    /// cache.remove(&"pomme".to_string())?;
     ///
-    /// for (key, value) in cache.iter() {
-    ///     println!("Clé: {}, Valeur: {}", key, value);
-    /// }
+    /// assert_eq!(events.try_recv().unwrap(), CacheEvent::Inserted { key: "pomme".to_string(), value: 1 });
+    /// assert_eq!(events.try_recv().unwrap(), CacheEvent::Removed { key: "pomme".to_string(), value: 1 });
+    /// assert!(events.try_recv().is_err());
+    /// # std::fs::remove_file("cache_watch.txt")?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn iter(&self) -> std::slice::Iter<(K, V)> {
-        self.cache.iter()
+    pub fn watch(&mut self, key: K) -> Receiver<CacheEvent<K, V>>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribe(KeyWatcher::new(key, sender));
+        receiver
     }
 
-    /// Retourne le nombre d'éléments dans le cache.
+    /// Enregistre les opérations de modification du cache dans un journal.
+    ///
+    /// Chaque `put`, `apply_patch`, `remove`, `clear`, `freeze`, `unfreeze`, `pin`, `unpin` et `expire` réussi, ainsi que chaque
+    /// élément importé, est ajouté à la fin du fichier sous forme d'une ligne JSON. Le journal peut
+    /// ensuite être rejoué sur un autre cache avec `replay` pour reproduire son état. Le chargement,
+    /// `restore_from` et les évictions ne sont pas enregistrés.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du journal ; il est créé s'il n'existe pas.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le cache, ou une erreur `CustomError` si le journal ne peut pas être ouvert.
     ///
     /// # Exemples
     ///
@@ -381,13 +742,2897 @@ where
     /// use eval_rust::errors::CustomError;
     ///
     /// # fn main() -> Result<(), CustomError> {
-    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache.txt")?;
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_with_operation_log.txt")?
+    ///     .with_operation_log("cache_with_operation_log.log")?;
     /// cache.put("pomme".to_string(), 1)?;
-    /// assert_eq!(cache.len(), 1);
+    /// cache.remove(&"pomme".to_string())?;
+    ///
+    /// // Reproduit les opérations sur un nouveau cache.
+    /// let mut replayed = CacheDB::<String, i32>::new_persistent(5, "cache_replayed.txt")?;
+    /// assert_eq!(replayed.replay("cache_with_operation_log.log")?, 2);
+    /// # std::fs::remove_file("cache_with_operation_log.txt")?;
+    /// # std::fs::remove_file("cache_with_operation_log.log")?;
+    /// # std::fs::remove_file("cache_replayed.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_operation_log(mut self, path: &str) -> Result<Self, CustomError> {
+        self.operation_log = Some(OperationLog::open(path)?);
+        Ok(self)
+    }
+
+    /// Rejoue sur ce cache les opérations d'un journal créé avec `with_operation_log`.
+    ///
+    /// Les opérations sont appliquées dans l'ordre avec les méthodes correspondantes ; la première
+    /// opération en échec interrompt le rejeu.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du journal.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nombre d'opérations rejouées, ou une erreur `CustomError` si le journal est
+    /// invalide ou si une opération échoue.
+    pub fn replay(&mut self, path: &str) -> Result<usize, CustomError> {
+        self.ensure_writable()?;
+        let contents = std::fs::read_to_string(path)?;
+
+        let mut count = 0;
+        for line in contents.lines().filter(|line| !line.is_empty()) {
+            let operation: Operation<K, V> = serde_json::from_str(line)?;
+            match operation {
+                Operation::Put { key, value } => {
+                    self.put(key, value)?;
+                }
+                Operation::ApplyPatch { key, patch } => self.apply_patch(&key, &patch)?,
+                Operation::Remove { key } => {
+                    self.remove(&key)?;
+                }
+                Operation::Clear => {
+                    self.clear()?;
+                }
+                Operation::Freeze { key } => self.freeze(&key)?,
+                Operation::Unfreeze { key } => self.unfreeze(&key)?,
+                Operation::Pin { key } => self.pin(&key)?,
+                Operation::Unpin { key } => self.unpin(&key)?,
+                Operation::Restore { key } => self.restore(&key)?,
+                Operation::Touch { key } => {
+                    self.touch(&key)?;
+                }
+                Operation::Expire { key, soft_ttl, hard_ttl } => self.expire(&key, soft_ttl, hard_ttl)?,
+            }
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Ajoute une opération au journal, s'il est activé.
+    fn log(&mut self, operation: &Operation<&K, &V>) -> Result<(), CustomError> {
+        match self.operation_log.as_mut() {
+            Some(operation_log) => operation_log.append(operation),
+            None => Ok(()),
+        }
+    }
+
+    /// Ajoute au journal l'insertion de l'élément le plus récemment utilisé, s'il est activé.
+    fn log_last_put(&mut self) -> Result<(), CustomError> {
+        if let (Some(operation_log), Some(record)) = (self.operation_log.as_mut(), self.cache.last()) {
+            operation_log.append(&Operation::Put { key: &record.key, value: &record.value })?;
+        }
+        Ok(())
+    }
+
+    /// Force la sauvegarde des modifications en attente.
+    ///
+    /// Sans persistance différée, le cache est simplement sauvegardé dans le fichier.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si le cache a été sauvegardé avec succès, ou une erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_flush.txt")?
+    ///     .with_write_behind(Duration::from_secs(60), 1000);
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.flush()?;
+    /// # std::fs::remove_file("cache_flush.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flush(&mut self) -> Result<(), CustomError> {
+        self.flush_to_file(false)
+    }
+
+    /// Sauvegarde le cache et réinitialise la persistance différée, en synchronisant le fichier sur
+    /// le disque si `sync` est vrai.
+    fn flush_to_file(&mut self, sync: bool) -> Result<(), CustomError> {
+        self.save_to_file(sync)?;
+        if let Some(write_behind) = self.write_behind.as_mut() {
+            write_behind.mark_flushed();
+        }
+        Ok(())
+    }
+
+    /// Sauvegarde les modifications en attente et attend qu'elles soient écrites sur le disque.
+    ///
+    /// Contrairement à `flush`, qui peut laisser les données dans les tampons du système, le fichier
+    /// du cache et le journal d'opérations éventuel sont synchronisés sur le disque avant le retour :
+    /// les modifications survivent alors à un arrêt brutal de la machine.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si les modifications sont durables, ou une erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_flush_barrier.txt")?
+    ///     .with_write_behind(Duration::from_secs(60), 1000);
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.flush_barrier()?;
+    /// # std::fs::remove_file("cache_flush_barrier.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flush_barrier(&mut self) -> Result<(), CustomError> {
+        self.flush_to_file(true)?;
+        if let Some(operation_log) = self.operation_log.as_mut() {
+            operation_log.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Persiste une modification, immédiatement ou selon la politique de persistance différée.
+    fn persist(&mut self) -> Result<(), CustomError> {
+        self.prune_listeners();
+        let should_flush = match self.write_behind.as_mut() {
+            Some(write_behind) => write_behind.record_mutation(),
+            None => true,
+        };
+        if should_flush {
+            self.flush()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sauvegarde le cache dans le fichier.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si le cache a été sauvegardé avec succès, `CustomError::CacheDbLockError` si le
+    /// fichier est verrouillé en mode partagé, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_save.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.save()?;
+    /// # std::fs::remove_file("cache_save.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save(&self) -> Result<(), CustomError> {
+        self.save_to_file(false)
+    }
+
+    /// Sauvegarde le cache dans son fichier, en le synchronisant sur le disque si `sync` est vrai.
+    fn save_to_file(&self, sync: bool) -> Result<(), CustomError> {
+        if self.is_read_only() {
+            return Err(CustomError::CacheDbLockError);
+        }
+        let started_at = Instant::now();
+        let file_size = self.write_to(&self.file_path, sync)?;
+
+        let duration = started_at.elapsed();
+        let mut metrics = self.persist_metrics.get();
+        metrics.record(duration, file_size);
+        self.persist_metrics.set(metrics);
+        self.record_slow("save", duration);
+        Ok(())
+    }
+
+    /// Écrit le contenu du cache dans le fichier spécifié et retourne la taille du fichier obtenu.
+    ///
+    /// Si `sync` est vrai, le fichier est synchronisé sur le disque avant le retour.
+    fn write_to(&self, path: &str, sync: bool) -> Result<u64, CustomError> {
+        let file = match OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+        {
+            Ok(file) => file,
+            Err(_) => return Err(CustomError::CacheDbSaveError),
+        };
+
+        let mut writer = BufWriter::new(file);
+
+        for migration in self.migration_history.iter() {
+            let migration_json = serde_json::to_string(migration)?;
+            if writeln!(writer, "{}{}", HISTORY_PREFIX, migration_json).is_err() {
+                return Err(CustomError::CacheDbSaveError);
+            }
+        }
+
+        for header in self.extra_headers.iter() {
+            if writeln!(writer, "{}", header).is_err() {
+                return Err(CustomError::CacheDbSaveError);
+            }
+        }
+
+        for record in self.cache.iter().chain(self.tombstones.iter()) {
+            let key_json = match serde_json::to_string(&record.key) {
+                Ok(json) => json,
+                Err(_) => return Err(CustomError::SerializationError(serde_json::Error::custom(
+                    "Failed to serialize key to JSON",
+                ))),
+            };
+            let value_json = match serde_json::to_string(&record.value) {
+                Ok(json) => json,
+                Err(_) => return Err(CustomError::SerializationError(serde_json::Error::custom(
+                    "Failed to serialize value to JSON",
+                ))),
+            };
+            let metadata_json = match record_metadata_to_json(record) {
+                Ok(json) => json,
+                Err(_) => return Err(CustomError::SerializationError(serde_json::Error::custom(
+                    "Failed to serialize metadata to JSON",
+                ))),
+            };
+            let mut line = format!("{}={}={}", key_json, value_json, metadata_json);
+            for field in record.extensions.fields.iter() {
+                line.push('=');
+                line.push_str(field);
+            }
+            append_line_checksum(&mut line);
+            line.push('\n');
+            if writer.write_all(line.as_bytes()).is_err() {
+                return Err(CustomError::CacheDbSaveError);
+            }
+        }
+
+        if writer.flush().is_err() {
+            return Err(CustomError::CacheDbSaveError);
+        }
+        if sync && writer.get_ref().sync_all().is_err() {
+            return Err(CustomError::CacheDbSaveError);
+        }
+
+        Ok(writer.get_ref().metadata()?.len())
+    }
+
+    /// Charge le cache à partir du fichier.
+    ///
+    /// Un cache créé avec `new_persistent_lenient` est chargé comme avec `load_lenient`, et le
+    /// rapport obtenu est conservé dans `recovery_report`.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si le cache a été chargé avec succès, ou une erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_load.txt")?;
+    /// cache.load()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load(&mut self) -> Result<(), CustomError> {
+        if self.lenient {
+            self.recovery_report = Some(self.load_lenient()?);
+            return Ok(());
+        }
+
+        self.load_contents(false)?;
+        Ok(())
+    }
+
+    /// Charge le cache à partir du fichier en ignorant les lignes illisibles ou corrompues.
+    ///
+    /// Les lignes ignorées ne sont pas réécrites lors de la sauvegarde qui suit le chargement ;
+    /// leur contenu est conservé dans le rapport retourné.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le rapport des lignes ignorées, ou une erreur `CustomError` si le fichier n'a pas pu être lu.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_load_lenient.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// let report = cache.load_lenient()?;
+    /// assert!(report.is_clean());
+    /// # std::fs::remove_file("cache_load_lenient.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_lenient(&mut self) -> Result<RecoveryReport, CustomError> {
+        let skipped = self.load_contents(true)?;
+        Ok(RecoveryReport { skipped })
+    }
+
+    /// Retourne le rapport du dernier chargement d'un cache créé avec `new_persistent_lenient`.
+    pub fn recovery_report(&self) -> Option<&RecoveryReport> {
+        self.recovery_report.as_ref()
+    }
+
+    /// Charge le fichier du cache puis le sauvegarde, et retourne les lignes ignorées en mode tolérant.
+    fn load_contents(&mut self, lenient: bool) -> Result<Vec<SkippedLine>, CustomError> {
+        let mut contents = match self.read_entries(&self.file_path, lenient)? {
+            Some(contents) => contents,
+            None => return Ok(Vec::new()),
+        };
+
+        let skipped = std::mem::take(&mut contents.skipped);
+        self.replace_contents(contents)?;
+        if !self.is_read_only() {
+            self.save()?;
+        }
+        Ok(skipped)
+    }
+
+    /// Remplace le contenu du cache en mémoire par celui d'un fichier, sans persister le cache.
+    ///
+    /// Les éléments expirés sont ignorés, et la politique de capacité est appliquée avant toute
+    /// modification du cache.
+    fn replace_contents(&mut self, contents: FileContents<K, V>) -> Result<(), CustomError> {
+        let FileContents { entries, history, extra_headers, .. } = contents;
+        let (tombstones, entries): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .filter(|record| !record.metadata.is_expired())
+            .partition(|record| record.metadata.deleted_at.is_some());
+
+        if entries.len() > self.capacity {
+            match self.capacity_policy {
+                CapacityPolicy::TruncateLru => {}
+                CapacityPolicy::Error => return Err(CustomError::CacheDbCapacityError),
+                CapacityPolicy::Grow => self.capacity = entries.len(),
+            }
+        }
+
+        self.migration_history = history;
+        self.extra_headers = extra_headers;
+        self.clear_entries();
+        for record in entries {
+            self.insert_record(record)?;
+        }
+        self.tombstones = tombstones;
+        Ok(())
+    }
+
+    /// Lit les éléments et l'historique des migrations stockés dans le fichier spécifié.
+    ///
+    /// Le fichier commence par une ligne d'en-tête par migration appliquée, suivie d'une ligne par
+    /// élément contenant la clé, la valeur et les métadonnées encodées en JSON et séparées par `=`,
+    /// puis la somme de contrôle CRC32 de la ligne. Une ligne dont la somme de contrôle ne correspond
+    /// pas est signalée par `CustomError::CacheDbCorruptedError` avec son numéro de ligne.
+    /// Les migrations absentes de l'historique sont appliquées avant de lire les éléments.
+    ///
+    /// Les données écrites par une version plus récente sont ignorées et conservées pour la
+    /// prochaine sauvegarde : en-têtes inconnus, champs inconnus des métadonnées et champs
+    /// supplémentaires en fin de ligne.
+    ///
+    /// En mode tolérant, les lignes illisibles ou corrompues sont ignorées et décrites dans
+    /// `FileContents::skipped` au lieu de faire échouer la lecture.
+    ///
+    /// Retourne `None` si le fichier n'existe pas.
+    fn read_entries(&self, path: &str, lenient: bool) -> Result<Option<FileContents<K, V>>, CustomError> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(_) => return Err(CustomError::CacheDbLoadError),
+        };
+
+        let mut reader = BufReader::new(file);
+        let mut contents = String::new();
+        if reader.read_to_string(&mut contents).is_err() {
+            return Err(CustomError::CacheDbLoadError);
+        }
+
+        let mut history = Vec::new();
+        let mut extra_headers = Vec::new();
+        let mut entry_lines = Vec::new();
+        let mut lines = Vec::new();
+        let mut line_numbers = Vec::new();
+        let mut raw_lines = Vec::new();
+        let mut skipped = Vec::new();
+        let mut skip = |line: usize, content: &str, error: CustomError| {
+            if !lenient {
+                return Err(error);
+            }
+            skipped.push(SkippedLine { line, content: content.to_string(), error });
+            Ok(())
+        };
+
+        for (index, line) in contents.lines().enumerate() {
+            match line.strip_prefix(HISTORY_PREFIX) {
+                Some(migration_json) => match serde_json::from_str(migration_json) {
+                    Ok(migration) => history.push(migration),
+                    Err(_) => skip(index + 1, line, CustomError::CacheDbLoadError)?,
+                },
+                None if line.starts_with('#') => extra_headers.push(line.to_string()),
+                None => entry_lines.push((index + 1, line)),
+            }
+        }
+
+        // Les fichiers marqués par la migration des sommes de contrôle doivent en porter une par ligne.
+        let checksums_required = history.iter().any(|migration: &MigrationRecord| migration.version == LINE_CHECKSUMS_VERSION);
+        for (number, line) in entry_lines {
+            match strip_line_checksum(line, checksums_required) {
+                Some(stripped) => {
+                    lines.push(stripped.to_string());
+                    line_numbers.push(number);
+                    raw_lines.push(line);
+                }
+                None => skip(number, line, CustomError::CacheDbCorruptedError { line: number })?,
+            }
+        }
+        let lines = migrations::migrate(lines, &mut history);
+
+        let mut entries = Vec::new();
+        for ((line, number), raw) in lines.into_iter().zip(line_numbers).zip(raw_lines) {
+            match line.and_then(|line| CacheDB::parse_entry(&line)) {
+                Ok(record) => entries.push(record),
+                Err(error) => skip(number, raw, error)?,
+            }
+        }
+
+        Ok(Some(FileContents { entries, history, extra_headers, skipped }))
+    }
+
+    /// Lit un élément à partir d'une ligne du fichier, une fois sa somme de contrôle retirée.
+    fn parse_entry(line: &str) -> Result<Record<K, V>, CustomError> {
+        let parts = split_line(line);
+        if parts.len() < 3 {
+            return Err(CustomError::CacheDbLoadError);
+        }
+        let key: K = match serde_json::from_str(parts[0]) {
+            Ok(key) => key,
+            Err(_) => return Err(CustomError::CacheDbLoadError),
+        };
+        let value: V = match serde_json::from_str(parts[1]) {
+            Ok(value) => value,
+            Err(_) => return Err(CustomError::CacheDbLoadError),
+        };
+        let (metadata, mut unknown_metadata) = match parse_metadata(parts[2]) {
+            Some(metadata) => metadata,
+            None => return Err(CustomError::CacheDbLoadError),
+        };
+        let history = match take_history(&mut unknown_metadata) {
+            Some(history) => history,
+            None => return Err(CustomError::CacheDbLoadError),
+        };
+        let extensions = Extensions {
+            metadata: unknown_metadata,
+            fields: parts[3..].iter().map(|field| field.to_string()).collect(),
+        };
+        Ok(Record { key, value, metadata, extensions, history, verified: false })
+    }
+
+    /// Écrit une copie cohérente du cache dans le fichier spécifié.
+    ///
+    /// Le fichier du cache n'est pas modifié ; la copie peut être rechargée avec `restore_from`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier de sauvegarde.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si la copie a été écrite avec succès, ou une erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_snapshot.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.snapshot("cache_snapshot.bak")?;
+    /// # std::fs::remove_file("cache_snapshot.txt")?;
+    /// # std::fs::remove_file("cache_snapshot.bak")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self, path: &str) -> Result<(), CustomError> {
+        self.write_to(path, false)?;
+        Ok(())
+    }
+
+    /// Duplique le cache dans un nouveau fichier et retourne le cache obtenu.
+    ///
+    /// La copie a la même capacité, la même politique de capacité et le même budget mémoire que le
+    /// cache. Les observateurs, la source de données, le journal d'opérations, la persistance
+    /// différée et le verrou du fichier ne sont pas copiés.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier de la copie.
+    ///
+    /// # Retour
+    ///
+    /// Retourne la copie, `CustomError::BadRequest` si `path` est le fichier du cache, ou une autre
+    /// erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_clone_to.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// let mut copy = cache.clone_to("cache_clone_to.copy")?;
+    /// copy.put("banane".to_string(), 2)?;
+    /// assert_eq!((cache.len(), copy.len()), (1, 2));
+    /// # std::fs::remove_file("cache_clone_to.txt")?;
+    /// # std::fs::remove_file("cache_clone_to.copy")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_to(&self, path: &str) -> Result<Self, CustomError> {
+        if path == self.file_path {
+            return Err(CustomError::BadRequest);
+        }
+        self.write_to(path, false)?;
+
+        let mut copy = CacheDB::new_persistent_with_options(self.capacity, path, self.capacity_policy, false)?;
+        if let Some(max_bytes) = self.max_bytes {
+            copy = copy.with_max_bytes(max_bytes);
+        }
+        copy.redact_debug = self.redact_debug;
+        Ok(copy)
+    }
+
+    /// Déplace le fichier du cache vers un nouveau chemin, par exemple sur un autre volume.
+    ///
+    /// Le cache est écrit dans le nouveau fichier, qui est relu pour vérifier qu'il contient tous
+    /// les éléments avant que le cache ne l'utilise. L'ancien fichier est ensuite supprimé si
+    /// possible : le cache utilisant déjà le nouveau fichier, un échec de cette suppression n'est
+    /// pas signalé. Si le fichier était verrouillé, le nouveau fichier est verrouillé dans le même
+    /// mode avant l'écriture. En cas d'erreur, le cache continue d'utiliser l'ancien fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le nouveau chemin du fichier du cache.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si le fichier a été déplacé, `CustomError::CacheDbSaveError` si la copie
+    /// n'a pas pu être vérifiée, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_save_as.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.save_as("cache_save_as.moved")?;
+    ///
+    /// assert!(!std::path::Path::new("cache_save_as.txt").exists());
+    /// cache.put("banane".to_string(), 2)?; // écrit dans le nouveau fichier
+    /// # std::fs::remove_file("cache_save_as.moved")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save_as(&mut self, path: &str) -> Result<(), CustomError> {
+        if path == self.file_path {
+            return self.flush();
+        }
+        if self.is_read_only() {
+            return Err(CustomError::CacheDbLockError);
+        }
+
+        let file_lock = match self.file_lock.as_ref() {
+            Some(lock) => Some(FileLock::acquire(path, lock.mode)?),
+            None => None,
+        };
+        let verified = self
+            .write_to(path, false)
+            .and_then(|_| self.read_entries(path, false))
+            .map(|contents| contents.is_some_and(|contents| contents.entries.len() == self.cache.len() + self.tombstones.len()));
+        if !matches!(verified, Ok(true)) {
+            drop(file_lock);
+            let _ = std::fs::remove_file(path);
+            return Err(verified.err().unwrap_or(CustomError::CacheDbSaveError));
+        }
+
+        let old_path = std::mem::replace(&mut self.file_path, path.to_string());
+        self.file_lock = file_lock;
+        if let Some(write_behind) = self.write_behind.as_mut() {
+            write_behind.mark_flushed();
+        }
+        let _ = std::fs::remove_file(old_path);
+        Ok(())
+    }
+
+    /// Remplace le contenu du cache par celui d'une copie créée avec `snapshot`.
+    ///
+    /// La copie est entièrement lue avant de modifier le cache : en cas d'erreur, le cache est inchangé.
+    /// Le contenu restauré est ensuite sauvegardé dans le fichier du cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier de sauvegarde.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si le cache a été restauré, `CustomError::NotFound` si la copie n'existe pas,
+    /// ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_restore_from.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.snapshot("cache_restore_from.bak")?;
+    ///
+    /// cache.clear()?;
+    /// cache.restore_from("cache_restore_from.bak")?;
+    /// assert_eq!(cache.len(), 1);
+    /// # std::fs::remove_file("cache_restore_from.txt")?;
+    /// # std::fs::remove_file("cache_restore_from.bak")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore_from(&mut self, path: &str) -> Result<(), CustomError> {
+        self.ensure_writable()?;
+        let contents = match self.read_entries(path, false)? {
+            Some(contents) => contents,
+            None => return Err(CustomError::NotFound),
+        };
+
+        self.replace_contents(contents)?;
+        self.flush()
+    }
+
+    /// Exporte le contenu du cache vers un flux, au format JSON ou CSV.
+    ///
+    /// Les éléments sont écrits dans l'ordre de `iter()`, sans les éléments expirés.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Le flux dans lequel écrire l'export.
+    /// * `format` - Le format de l'export.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si l'export a réussi, ou une erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::{CacheDB, ExportFormat};
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_export_to_writer.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// let mut csv = Vec::new();
+    /// cache.export_to_writer(&mut csv, ExportFormat::Csv)?;
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "key,value\n\"\"\"pomme\"\"\",1\n");
+    /// # std::fs::remove_file("cache_export_to_writer.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_to_writer<W: Write>(&self, writer: W, format: ExportFormat) -> Result<(), CustomError> {
+        let mut writer = BufWriter::new(writer);
+
+        match format {
+            ExportFormat::Json => {
+                let entries: Vec<ExportEntry<&K, &V>> = self
+                    .live_records()
+                    .map(|record| ExportEntry { key: &record.key, value: &record.value })
+                    .collect();
+                serde_json::to_writer(&mut writer, &entries)?;
+            }
+            ExportFormat::Csv => {
+                writeln!(writer, "{}", CSV_HEADER)?;
+                for record in self.live_records() {
+                    let key_json = serde_json::to_string(&record.key)?;
+                    let value_json = serde_json::to_string(&record.value)?;
+                    writeln!(writer, "{},{}", csv_field(&key_json), csv_field(&value_json))?;
+                }
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Importe dans le cache des éléments exportés avec `export_to_writer`.
+    ///
+    /// Les éléments sont ajoutés au contenu existant comme avec `put`, dans l'ordre du flux,
+    /// puis le cache est persisté une seule fois. Le flux est entièrement lu avant de modifier le cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Le flux contenant l'export.
+    /// * `format` - Le format de l'export.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nombre d'éléments importés, ou une erreur `CustomError` si le flux est invalide.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::{CacheDB, ExportFormat};
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_import_from_reader.txt")?;
+    /// let json = r#"[{"key":"pomme","value":1},{"key":"banane","value":2}]"#;
+    ///
+    /// let imported = cache.import_from_reader(json.as_bytes(), ExportFormat::Json)?;
+    /// assert_eq!(imported, 2);
+    /// # std::fs::remove_file("cache_import_from_reader.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn import_from_reader<R: Read>(&mut self, reader: R, format: ExportFormat) -> Result<usize, CustomError> {
+        self.ensure_writable()?;
+        let mut reader = BufReader::new(reader);
+
+        let entries: Vec<(K, V)> = match format {
+            ExportFormat::Json => {
+                let entries: Vec<ExportEntry<K, V>> = serde_json::from_reader(reader)?;
+                entries.into_iter().map(|entry| (entry.key, entry.value)).collect()
+            }
+            ExportFormat::Csv => {
+                let mut contents = String::new();
+                reader.read_to_string(&mut contents)?;
+
+                let mut lines = contents.lines();
+                if lines.next() != Some(CSV_HEADER) {
+                    return Err(CustomError::BadRequest);
+                }
+
+                let mut entries = Vec::new();
+                for line in lines.filter(|line| !line.is_empty()) {
+                    let fields = parse_csv_line(line)?;
+                    if fields.len() != 2 {
+                        return Err(CustomError::BadRequest);
+                    }
+                    let key: K = serde_json::from_str(&fields[0])?;
+                    let value: V = serde_json::from_str(&fields[1])?;
+                    entries.push((key, value));
+                }
+                entries
+            }
+        };
+
+        for (key, _) in entries.iter() {
+            if self.live_position(key).is_some_and(|index| self.cache[index].metadata.frozen) {
+                return Err(CustomError::Locked);
+            }
+        }
+
+        let count = entries.len();
+        for (key, value) in entries {
+            self.write_through(&key, &value)?;
+            self.insert(key, value)?;
+            self.log_last_put()?;
+        }
+        self.persist()?;
+
+        Ok(count)
+    }
+
+    /// Insère une paire clé-valeur dans le cache.
+    ///
+    /// Si la clé existe déjà, la valeur associée est mise à jour.
+    /// Si le cache est plein, l'élément le moins récemment utilisé est supprimé.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à insérer.
+    /// * `value` - La valeur à associer à la clé.
+    ///
+    /// # Retour
+    ///
+    /// Retourne la valeur précédemment associée à la clé, ou `None` si la clé n'était pas dans le cache,
+    /// `CustomError::Locked` si la clé est gelée, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_put.txt")?;
+    /// assert_eq!(cache.put("pomme".to_string(), 1)?, None);
+    /// assert_eq!(cache.put("pomme".to_string(), 2)?, Some(1));
+    /// # std::fs::remove_file("cache_put.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Result<Option<V>, CustomError> {
+        self.timed("put", |cache| {
+            cache.ensure_writable()?;
+            cache.write_through(&key, &value)?;
+            let previous = cache.insert(key, value)?;
+            cache.log_last_put()?;
+            cache.persist()?;
+            Ok(previous)
+        })
+    }
+
+    /// Insère une paire clé-valeur comme avec `put`, uniquement si la clé n'est pas déjà dans le cache.
+    ///
+    /// La vérification et l'insertion se font sous le même emprunt du cache : deux producteurs qui
+    /// partagent le cache derrière un `Mutex` ne peuvent pas insérer tous les deux la même clé.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à insérer.
+    /// * `value` - La valeur à associer à la clé.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le numéro de version de la valeur insérée, `CustomError::PreconditionFailed` si la clé
+    /// est déjà dans le cache, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_put_if_absent.txt")?;
+    /// assert_eq!(cache.put_if_absent("verrou".to_string(), 1)?, 1);
+    /// assert!(matches!(cache.put_if_absent("verrou".to_string(), 2), Err(CustomError::PreconditionFailed)));
+    /// # std::fs::remove_file("cache_put_if_absent.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_if_absent(&mut self, key: K, value: V) -> Result<u64, CustomError> {
+        self.ensure_writable()?;
+        if self.live_position(&key).is_some() {
+            return Err(CustomError::PreconditionFailed);
+        }
+        self.put_versioned(key, value)
+    }
+
+    /// Remplace la valeur associée à une clé comme avec `put`, uniquement si sa version est celle attendue.
+    ///
+    /// Permet à des producteurs concurrents de modifier une valeur lue sans écraser une
+    /// modification faite entre-temps (verrouillage optimiste).
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé de l'élément.
+    /// * `value` - La nouvelle valeur.
+    /// * `expected_version` - Le numéro de version lu avec `metadata`.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nouveau numéro de version, `CustomError::NotFound` si la clé n'est pas dans le cache,
+    /// `CustomError::PreconditionFailed` si la version ne correspond pas, ou une autre erreur `CustomError`
+    /// si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_put_if_version.txt")?;
+    /// cache.put("compteur".to_string(), 1)?;
+    /// let version = cache.metadata(&"compteur".to_string()).unwrap().version;
+    ///
+    /// assert_eq!(cache.put_if_version("compteur".to_string(), 2, version)?, version + 1);
+    /// assert!(matches!(cache.put_if_version("compteur".to_string(), 3, version), Err(CustomError::PreconditionFailed)));
+    /// # std::fs::remove_file("cache_put_if_version.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_if_version(&mut self, key: K, value: V, expected_version: u64) -> Result<u64, CustomError> {
+        self.ensure_writable()?;
+        match self.live_position(&key) {
+            None => return Err(CustomError::NotFound),
+            Some(index) if self.cache[index].metadata.version != expected_version => return Err(CustomError::PreconditionFailed),
+            Some(_) => {}
+        }
+        self.put_versioned(key, value)
+    }
+
+    /// Insère une paire clé-valeur comme avec `put` et retourne le numéro de version de la valeur.
+    fn put_versioned(&mut self, key: K, value: V) -> Result<u64, CustomError> {
+        self.put(key.clone(), value)?;
+        self.metadata(&key).map(|metadata| metadata.version).ok_or(CustomError::NotFound)
+    }
+
+    /// Insère une paire clé-valeur dans le cache, sans retourner la valeur remplacée.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si l'insertion a réussi, ou une erreur `CustomError` comme `put`.
+    #[deprecated(note = "utilisez `put`, qui retourne la valeur remplacée")]
+    pub fn set(&mut self, key: K, value: V) -> Result<(), CustomError> {
+        self.put(key, value).map(|_| ())
+    }
+
+    /// Insère une paire clé-valeur dans le cache avec des durées de vie.
+    ///
+    /// Une fois la durée de vie souple écoulée, l'élément reste lisible mais est signalé comme
+    /// périmé par `EntryMetadata::is_stale`. Une fois la durée de vie stricte écoulée, l'élément
+    /// expire : il n'est plus retourné par `get` et est supprimé du cache. Les durées sont
+    /// décomptées à partir de l'insertion et relancées à chaque modification de la valeur.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à insérer.
+    /// * `value` - La valeur à associer à la clé.
+    /// * `soft_ttl` - La durée de vie souple, ou `None`.
+    /// * `hard_ttl` - La durée de vie stricte, ou `None`.
+    ///
+    /// # Retour
+    ///
+    /// Retourne la valeur précédemment associée à la clé, ou `None` si la clé n'était pas dans le cache,
+    /// `CustomError::Locked` si la clé est gelée, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_put_with_ttl.txt")?;
+    /// cache.put_with_ttl("pomme".to_string(), 1, Some(Duration::from_secs(60)), Some(Duration::from_secs(3600)))?;
+    ///
+    /// let metadata = cache.metadata(&"pomme".to_string()).unwrap();
+    /// assert!(!metadata.is_stale());
+    /// assert!(metadata.expires_at().is_some());
+    /// # std::fs::remove_file("cache_put_with_ttl.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_with_ttl(&mut self, key: K, value: V, soft_ttl: Option<Duration>, hard_ttl: Option<Duration>) -> Result<Option<V>, CustomError> {
+        self.timed("put_with_ttl", |cache| {
+            cache.ensure_writable()?;
+            cache.write_through(&key, &value)?;
+            let previous = cache.insert(key, value)?;
+            cache.log_last_put()?;
+            if let Some(record) = cache.cache.last_mut() {
+                record.metadata.set_ttl(soft_ttl, hard_ttl);
+            }
+            if let (Some(operation_log), Some(record)) = (cache.operation_log.as_mut(), cache.cache.last()) {
+                operation_log.append(&Operation::<&K, &V>::Expire { key: &record.key, soft_ttl, hard_ttl })?;
+            }
+            cache.persist()?;
+            Ok(previous)
+        })
+    }
+
+    /// Écrit une valeur dans la source de données, si elle est configurée, avant son insertion.
+    ///
+    /// Retourne `CustomError::Locked` sans écrire dans la source si la clé est gelée.
+    fn write_through(&self, key: &K, value: &V) -> Result<(), CustomError> {
+        let store = match self.backing_store.as_ref() {
+            Some(store) => store,
+            None => return Ok(()),
+        };
+        if self.metadata(key).is_some_and(|metadata| metadata.frozen) {
+            return Err(CustomError::Locked);
+        }
+        store.write_on_put(key, value)
+    }
+
+    /// Recherche dans la source de données une clé absente du cache et l'insère comme avec `put`.
+    ///
+    /// Retourne `None` si aucune source n'est configurée ou si la clé n'y est pas.
+    fn read_through(&mut self, key: &K) -> Result<Option<&V>, CustomError> {
+        let value = match self.backing_store.as_ref() {
+            Some(store) => store.fetch_on_miss(key)?,
+            None => None,
+        };
+        let value = match value {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        self.insert(key.clone(), value)?;
+        self.log_last_put()?;
+        self.persist()?;
+        Ok(self.cache.last().map(|record| &record.value))
+    }
+
+    /// Insère une paire clé-valeur en mémoire, sans persister le cache.
+    ///
+    /// Si la clé existe déjà, ses métadonnées sont conservées et mises à jour.
+    /// Retourne la valeur remplacée, ou `CustomError::Locked` si la clé est gelée.
+    fn insert(&mut self, key: K, value: V) -> Result<Option<V>, CustomError> {
+        let existing = self.live_position(&key).map(|index| &self.cache[index]);
+        let (metadata, extensions, history) = match existing {
+            Some(record) if record.metadata.frozen => return Err(CustomError::Locked),
+            Some(record) => {
+                let mut metadata = record.metadata;
+                metadata.record_update();
+                let mut history = Vec::new();
+                if self.history_depth > 0 {
+                    history = record.history.clone();
+                    history.push(ValueVersion {
+                        version: record.metadata.version,
+                        value: record.value.clone(),
+                        updated_at: record.metadata.updated_at,
+                    });
+                    let excess = history.len().saturating_sub(self.history_depth);
+                    history.drain(..excess);
+                }
+                (metadata, record.extensions.clone(), history)
+            }
+            None => (EntryMetadata::new(), Extensions::default(), Vec::new()),
+        };
+        let mut metadata = metadata;
+        metadata.checksum = value_checksum(&value);
+        let replaced = self.insert_record(Record { key, value, metadata, extensions, history, verified: true })?;
+
+        if let Some(record) = self.cache.last() {
+            self.tombstones.retain(|tombstone| tombstone.key != record.key);
+            for listener in self.listeners.iter() {
+                listener.on_insert(&record.key, &record.value);
+            }
+        }
+        Ok(replaced.map(|record| record.value))
+    }
+
+    /// Insère un élément en mémoire comme élément le plus récemment utilisé, sans persister le cache.
+    ///
+    /// Les éléments les moins récemment utilisés sont supprimés tant que la capacité
+    /// ou le budget mémoire est dépassé. Retourne l'élément remplacé ayant la même clé.
+    fn insert_record(&mut self, record: Record<K, V>) -> Result<Option<Record<K, V>>, CustomError> {
+        let size = entry_size(&record.key, &record.value);
+        if self.max_bytes.is_some_and(|max_bytes| size > max_bytes) {
+            return Err(CustomError::CacheDbCapacityError);
+        }
+
+        let replaced = match self.position(&record.key) {
+            Some(index) => Some(self.remove_at(index)),
+            None => {
+                if self.cache.len() >= self.capacity {
+                    self.evict_lru()?;
+                }
+                None
+            }
+        };
+
+        while self.max_bytes.is_some_and(|max_bytes| self.used_bytes + size > max_bytes) {
+            if let Err(e) = self.evict_lru() {
+                if let Some(replaced) = replaced {
+                    self.used_bytes += entry_size(&replaced.key, &replaced.value);
+                    self.cache.push(replaced);
+                }
+                return Err(e);
+            }
+        }
+
+        self.cache.push(record);
+        self.used_bytes += size;
+        Ok(replaced)
+    }
+
+    /// Retourne l'indice de l'élément associé à une clé.
+    pub(crate) fn position(&self, key: &K) -> Option<usize> {
+        self.cache.position(key)
+    }
+
+    /// Retourne l'indice de l'élément associé à une clé, en supprimant l'élément s'il a expiré.
+    fn live_position(&mut self, key: &K) -> Option<usize> {
+        let index = self.position(key)?;
+        if self.cache[index].metadata.is_expired() {
+            self.expire_at(index);
+            return None;
+        }
+        Some(index)
+    }
+
+    /// Supprime l'élément expiré à l'indice donné et notifie les observateurs.
+    fn expire_at(&mut self, index: usize) {
+        let record = self.remove_at(index);
+        for listener in self.listeners.iter() {
+            listener.on_expire(&record.key, &record.value);
+        }
+    }
+
+    /// Supprime l'élément à l'indice donné en mettant à jour la mémoire utilisée.
+    fn remove_at(&mut self, index: usize) -> Record<K, V> {
+        let record = self.cache.remove(index);
+        self.used_bytes -= entry_size(&record.key, &record.value);
+        record
+    }
+
+    /// Évince l'élément non épinglé le moins récemment utilisé et notifie les observateurs.
+    ///
+    /// Retourne `CustomError::CacheDbCapacityError` si tous les éléments du cache sont épinglés.
+    fn evict_lru(&mut self) -> Result<(), CustomError> {
+        if self.cache.is_empty() {
+            return Ok(());
+        }
+        let index = self.cache.iter().find(|record| !record.metadata.pinned).and_then(|record| self.position(&record.key));
+        let record = match index {
+            Some(index) => self.remove_at(index),
+            None => return Err(CustomError::CacheDbCapacityError),
+        };
+        self.access_counters.evictions += 1;
+        for listener in self.listeners.iter() {
+            listener.on_evict(&record.key, &record.value);
+        }
+        Ok(())
+    }
+
+    /// Supprime tous les éléments en mémoire, sans persister le cache.
+    fn clear_entries(&mut self) {
+        self.cache.clear();
+        self.tombstones.clear();
+        self.used_bytes = 0;
+    }
+
+    /// Conserve un élément supprimé si la suppression réversible est activée, et l'oublie sinon.
+    fn discard(&mut self, mut record: Record<K, V>) {
+        if self.soft_delete.is_some() {
+            record.metadata.deleted_at = Some(SystemTime::now());
+            self.tombstones.retain(|tombstone| tombstone.key != record.key);
+            self.tombstones.push(record);
+        }
+    }
+
+    /// Modifie la valeur associée à une clé en lui appliquant un JSON Merge Patch (RFC 7396).
+    ///
+    /// La valeur est convertie en JSON, le patch y est appliqué, puis le résultat est reconverti
+    /// en `V` et enregistré comme avec `put` : l'élément devient le plus récemment utilisé et le
+    /// cache est persisté une seule fois.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé de la valeur à modifier.
+    /// * `patch` - Le patch à appliquer.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si la valeur a été modifiée, `CustomError::NotFound` si la clé n'est pas dans le cache,
+    /// `CustomError::Locked` si la clé est gelée, ou `CustomError::SerializationError` si le résultat
+    /// n'est pas une valeur `V` valide.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use serde_json::{json, Value};
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, Value>::new_persistent(5, "cache_apply_patch.txt")?;
+    /// cache.put("pomme".to_string(), json!({"couleur": "verte", "prix": 2}))?;
+    ///
+    /// cache.apply_patch(&"pomme".to_string(), &json!({"couleur": "rouge", "prix": null}))?;
+    /// assert_eq!(cache.get(&"pomme".to_string()), Some(&json!({"couleur": "rouge"})));
+    /// # std::fs::remove_file("cache_apply_patch.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn apply_patch(&mut self, key: &K, patch: &serde_json::Value) -> Result<(), CustomError> {
+        self.ensure_writable()?;
+        let record = match self.live_position(key) {
+            Some(index) => &self.cache[index],
+            None => return Err(CustomError::NotFound),
+        };
+
+        let mut document = serde_json::to_value(&record.value)?;
+        merge_patch(&mut document, patch);
+        let patched: V = serde_json::from_value(document)?;
+
+        self.write_through(key, &patched)?;
+        self.insert(key.clone(), patched)?;
+        self.log(&Operation::<&K, &V>::ApplyPatch { key, patch: patch.clone() })?;
+        self.persist()
+    }
+
+    /// Gèle l'élément associé à une clé.
+    ///
+    /// Tant qu'il est gelé, les modifications de l'élément (`put`, `apply_patch`, import) et sa
+    /// suppression avec `remove` sont refusées avec `CustomError::Locked`. L'élément reste soumis
+    /// à l'éviction et à `clear`. L'état gelé est persisté avec l'élément.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à geler.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si l'élément a été gelé, `CustomError::NotFound` si la clé n'est pas dans le cache ou a expiré,
+    /// ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_freeze.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.freeze(&"pomme".to_string())?;
+    ///
+    /// assert!(matches!(cache.put("pomme".to_string(), 2), Err(CustomError::Locked)));
+    ///
+    /// cache.unfreeze(&"pomme".to_string())?;
+    /// cache.put("pomme".to_string(), 2)?;
+    /// # std::fs::remove_file("cache_freeze.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn freeze(&mut self, key: &K) -> Result<(), CustomError> {
+        self.set_frozen(key, true)
+    }
+
+    /// Dégèle l'élément associé à une clé, qui peut de nouveau être modifié et supprimé.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à dégeler.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si l'élément a été dégelé, `CustomError::NotFound` si la clé n'est pas dans le cache ou a expiré,
+    /// ou une autre erreur `CustomError` si une erreur s'est produite.
+    pub fn unfreeze(&mut self, key: &K) -> Result<(), CustomError> {
+        self.set_frozen(key, false)
+    }
+
+    /// Modifie l'état gelé d'un élément et persiste le cache.
+    fn set_frozen(&mut self, key: &K, frozen: bool) -> Result<(), CustomError> {
+        self.ensure_writable()?;
+        match self.live_position(key) {
+            Some(index) => self.cache[index].metadata.frozen = frozen,
+            None => return Err(CustomError::NotFound),
+        }
+        let operation = if frozen {
+            Operation::<&K, &V>::Freeze { key }
+        } else {
+            Operation::<&K, &V>::Unfreeze { key }
+        };
+        self.log(&operation)?;
+        self.persist()
+    }
+
+    /// Épingle l'élément associé à une clé pour le protéger de l'éviction.
+    ///
+    /// Un élément épinglé n'est jamais évincé pour respecter la capacité ou le budget mémoire du
+    /// cache : les éléments non épinglés les moins récemment utilisés le sont à sa place. Si tous
+    /// les éléments sont épinglés, une insertion qui nécessite une éviction échoue avec
+    /// `CustomError::CacheDbCapacityError`. L'élément reste soumis à l'expiration, à `remove` et à
+    /// `clear`. L'état épinglé est persisté avec l'élément.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à épingler.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si l'élément a été épinglé, `CustomError::NotFound` si la clé n'est pas dans le cache,
+    /// ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(2, "cache_pin.txt")?;
+    /// cache.put("config".to_string(), 1)?;
+    /// cache.pin(&"config".to_string())?;
+    ///
+    /// cache.put("pomme".to_string(), 2)?;
+    /// cache.put("banane".to_string(), 3)?; // "pomme" est évincé à la place de "config"
+    /// assert!(cache.contains_key(&"config".to_string()));
+    ///
+    /// cache.unpin(&"config".to_string())?;
+    /// # std::fs::remove_file("cache_pin.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pin(&mut self, key: &K) -> Result<(), CustomError> {
+        self.set_pinned(key, true)
+    }
+
+    /// Désépingle l'élément associé à une clé, qui peut de nouveau être évincé.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à désépingler.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si l'élément a été désépinglé, `CustomError::NotFound` si la clé n'est pas dans le cache,
+    /// ou une autre erreur `CustomError` si une erreur s'est produite.
+    pub fn unpin(&mut self, key: &K) -> Result<(), CustomError> {
+        self.set_pinned(key, false)
+    }
+
+    /// Modifie l'état épinglé d'un élément et persiste le cache.
+    fn set_pinned(&mut self, key: &K, pinned: bool) -> Result<(), CustomError> {
+        self.ensure_writable()?;
+        match self.live_position(key) {
+            Some(index) => self.cache[index].metadata.pinned = pinned,
+            None => return Err(CustomError::NotFound),
+        }
+        let operation = if pinned {
+            Operation::<&K, &V>::Pin { key }
+        } else {
+            Operation::<&K, &V>::Unpin { key }
+        };
+        self.log(&operation)?;
+        self.persist()
+    }
+
+    /// Définit les durées de vie de l'élément associé à une clé et relance leur décompte.
+    ///
+    /// Voir `put_with_ttl` pour la signification des durées de vie. Passer `None` pour les deux
+    /// durées rend l'élément permanent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé de l'élément.
+    /// * `soft_ttl` - La durée de vie souple, ou `None`.
+    /// * `hard_ttl` - La durée de vie stricte, ou `None`.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si les durées de vie ont été définies, `CustomError::NotFound` si la clé n'est pas dans le cache,
+    /// `CustomError::Locked` si la clé est gelée, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_expire.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// cache.expire(&"pomme".to_string(), None, Some(Duration::ZERO))?;
+    /// assert_eq!(cache.get(&"pomme".to_string()), None);
+    /// # std::fs::remove_file("cache_expire.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn expire(&mut self, key: &K, soft_ttl: Option<Duration>, hard_ttl: Option<Duration>) -> Result<(), CustomError> {
+        self.ensure_writable()?;
+        let record = match self.live_position(key) {
+            Some(index) => &mut self.cache[index],
+            None => return Err(CustomError::NotFound),
+        };
+        if record.metadata.frozen {
+            return Err(CustomError::Locked);
+        }
+        record.metadata.set_ttl(soft_ttl, hard_ttl);
+
+        self.log(&Operation::<&K, &V>::Expire { key, soft_ttl, hard_ttl })?;
+        self.persist()
+    }
+
+    /// Marque l'élément associé à une clé comme récemment utilisé sans lire sa valeur, puis persiste le cache.
+    ///
+    /// Si l'élément a des durées de vie, leur décompte est relancé, sauf si l'élément est gelé.
+    /// Le compteur de lectures n'est pas modifié. Utile pour garder en vie un élément volumineux
+    /// sans transférer sa valeur.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé de l'élément.
+    ///
+    /// # Retour
+    ///
+    /// Retourne les métadonnées mises à jour de l'élément, `CustomError::NotFound` si la clé n'est pas dans le cache,
+    /// ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(2, "cache_touch.txt")?;
+    /// cache.put_with_ttl("session".to_string(), 1, None, Some(Duration::from_secs(60)))?;
+    /// cache.put("pomme".to_string(), 2)?;
+    ///
+    /// let metadata = cache.touch(&"session".to_string())?;
+    /// assert_eq!(metadata.hit_count, 0);
+    ///
+    /// cache.put("banane".to_string(), 3)?; // "pomme" est évincé à la place de "session"
+    /// assert!(cache.contains_key(&"session".to_string()));
+    /// # std::fs::remove_file("cache_touch.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn touch(&mut self, key: &K) -> Result<EntryMetadata, CustomError> {
+        self.ensure_writable()?;
+        let index = match self.live_position(key) {
+            Some(index) => index,
+            None => return Err(CustomError::NotFound),
+        };
+        self.cache[index].metadata.record_touch();
+        let metadata = self.cache[index].metadata;
+        self.cache.move_to_back(index);
+
+        self.log(&Operation::<&K, &V>::Touch { key })?;
+        self.persist()?;
+        Ok(metadata)
+    }
+
+    /// Supprime tous les éléments expirés et persiste le cache s'il a été modifié.
+    ///
+    /// Sans appel à cette méthode, un élément expiré n'est supprimé que lors de sa prochaine
+    /// lecture ou au chargement du fichier. Les observateurs sont notifiés avec `on_expire`.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nombre d'éléments supprimés, ou une erreur `CustomError` si la sauvegarde a échoué.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_purge_expired.txt")?;
+    /// cache.put_with_ttl("pomme".to_string(), 1, None, Some(Duration::ZERO))?;
+    /// cache.put("banane".to_string(), 2)?;
+    ///
+    /// assert_eq!(cache.purge_expired()?, 1);
+    /// assert_eq!(cache.len(), 1);
+    /// # std::fs::remove_file("cache_purge_expired.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn purge_expired(&mut self) -> Result<usize, CustomError> {
+        self.ensure_writable()?;
+        let expired: Vec<K> = self
+            .cache
+            .iter()
+            .filter(|record| record.metadata.is_expired())
+            .map(|record| record.key.clone())
+            .collect();
+        let count = expired.len();
+        for key in expired.iter() {
+            if let Some(index) = self.position(key) {
+                self.expire_at(index);
+            }
+        }
+
+        if count > 0 {
+            self.persist()?;
+        }
+        Ok(count)
+    }
+
+    /// Indique si un élément supprimé est encore dans son délai de grâce, et peut donc être rétabli.
+    fn within_grace_period(&self, tombstone: &Record<K, V>, now: SystemTime) -> bool {
+        let purge_at = self.soft_delete.and_then(|grace_period| tombstone.metadata.deleted_at?.checked_add(grace_period));
+        purge_at.is_some_and(|purge_at| purge_at > now)
+    }
+
+    /// Rétablit un élément supprimé pendant le délai de grâce de la suppression réversible, puis persiste le cache.
+    ///
+    /// L'élément retrouve sa valeur et ses métadonnées, et devient l'élément le plus récemment utilisé.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé de l'élément à rétablir.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si l'élément a été rétabli, `CustomError::NotFound` si aucun élément supprimé
+    /// n'est conservé pour cette clé ou si son délai de grâce est écoulé, même s'il n'a pas encore été
+    /// purgé, ou une autre erreur `CustomError` si une erreur s'est produite.
+    pub fn restore(&mut self, key: &K) -> Result<(), CustomError> {
+        self.ensure_writable()?;
+        let now = SystemTime::now();
+        let position = match self.tombstones.iter().position(|tombstone| tombstone.key == *key) {
+            Some(position) if self.within_grace_period(&self.tombstones[position], now) => position,
+            _ => return Err(CustomError::NotFound),
+        };
+        let mut record = self.tombstones.remove(position);
+        record.metadata.deleted_at = None;
+        if let Err(e) = self.insert_record(record.clone()) {
+            self.tombstones.insert(position, record);
+            return Err(e);
+        }
+        for listener in self.listeners.iter() {
+            listener.on_insert(&record.key, &record.value);
+        }
+
+        self.log(&Operation::<&K, &V>::Restore { key })?;
+        self.persist()
+    }
+
+    /// Supprime définitivement les éléments supprimés dont le délai de grâce est écoulé, et persiste
+    /// le cache s'il a été modifié.
+    ///
+    /// Si la suppression réversible n'est pas activée, tous les éléments supprimés conservés dans
+    /// le fichier sont supprimés.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nombre d'éléments définitivement supprimés, ou une erreur `CustomError` si la sauvegarde a échoué.
+    pub fn purge_tombstones(&mut self) -> Result<usize, CustomError> {
+        self.ensure_writable()?;
+        let now = SystemTime::now();
+        let count = self.tombstones.len();
+        let tombstones = std::mem::take(&mut self.tombstones);
+        self.tombstones = tombstones.into_iter().filter(|tombstone| self.within_grace_period(tombstone, now)).collect();
+
+        let purged = count - self.tombstones.len();
+        if purged > 0 {
+            self.persist()?;
+        }
+        Ok(purged)
+    }
+
+    /// Récupère la valeur associée à une clé, en la chargeant avec une fonction si elle est absente.
+    ///
+    /// La clé est d'abord recherchée comme avec `get`, y compris dans la source de données
+    /// éventuelle. Si elle est absente, la valeur retournée par `loader` est insérée comme avec
+    /// `put`. Pour partager un cache entre threads sans charger plusieurs fois la même clé,
+    /// utilisez `SingleFlight`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    /// * `loader` - La fonction qui charge la valeur d'une clé absente.
+    ///
+    /// # Retour
+    ///
+    /// Retourne une référence vers la valeur, l'erreur de la fonction de chargement, ou une erreur
+    /// `CustomError` convertie si le cache n'a pas pu être lu ou modifié.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, usize>::new_persistent(5, "cache_get_or_insert_with.txt")?;
+    ///
+    /// let value = cache.get_or_insert_with("pomme".to_string(), || Ok::<_, CustomError>(5))?;
+    /// assert_eq!(value, &5);
+    /// # std::fs::remove_file("cache_get_or_insert_with.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_or_insert_with<F, E>(&mut self, key: K, loader: F) -> Result<&V, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+        E: From<CustomError>,
+    {
+        if self.try_get(&key)?.is_none() {
+            self.ensure_writable()?;
+            let value = loader()?;
+            self.put(key.clone(), value)?;
+        }
+        Ok(self.peek(&key).ok_or(CustomError::NotFound)?)
+    }
+
+    /// Retourne l'élément associé à une clé, pour le lire ou le modifier sans rechercher la clé deux fois.
+    ///
+    /// Un élément expiré ou dont la valeur ne correspond plus à sa somme de contrôle est considéré
+    /// comme absent. La source de données n'est pas consultée.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé de l'élément.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_entry.txt")?;
+    ///
+    /// // Compte les visites : insère 1, puis incrémente la valeur existante.
+    /// for _ in 0..3 {
+    ///     cache.entry("visites".to_string()).and_modify(|count| *count += 1)?.or_insert(1)?;
+    /// }
+    /// assert_eq!(cache.get(&"visites".to_string()), Some(&3));
+    /// # std::fs::remove_file("cache_entry.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        // L'élément est vérifié une seule fois ici : un élément présent reste lisible par son indice
+        // même s'il expire avant d'être lu.
+        match self.live_position(&key).filter(|&index| self.verify_at(index).is_ok()) {
+            Some(index) => Entry::Occupied(OccupiedEntry { cache: self, key, index }),
+            None => Entry::Vacant(VacantEntry { cache: self, key }),
+        }
+    }
+
+    /// Récupère la valeur associée à une clé dans le cache.
+    ///
+    /// Si la clé est trouvée, la valeur correspondante est retournée et l'élément est marqué comme récemment utilisé.
+    /// Son compteur de lectures et sa date de dernier accès sont mis à jour.
+    ///
+    /// Un élément expiré est supprimé du cache et `None` est retourné ; la suppression est persistée
+    /// lors de la prochaine sauvegarde.
+    ///
+    /// Si une source de données est configurée avec `with_backing_store`, une clé absente y est
+    /// recherchée et insérée dans le cache. Les erreurs de la source et les valeurs corrompues sont
+    /// traitées comme une absence de la clé ; utilisez `try_get` pour les distinguer.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Some(&V)` contenant une référence à la valeur associée à la clé si elle est trouvée, ou `None` si la clé n'est pas dans le cache.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_get.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// if let Some(valeur) = cache.get(&"pomme".to_string()) {
+    ///     println!("La valeur de pomme est: {}", valeur);
+    /// }
+    /// # std::fs::remove_file("cache_get.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.try_get(key).ok().flatten()
+    }
+
+    /// Récupère la valeur associée à une clé comme `get`, en retournant les erreurs au lieu de les ignorer.
+    ///
+    /// À sa première lecture après le chargement, la valeur est comparée à la somme de contrôle
+    /// enregistrée dans ses métadonnées. Une valeur corrompue n'est pas retournée : l'élément est
+    /// supprimé du cache et les observateurs sont notifiés avec `on_corrupted`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(Some(&V))` si la clé est trouvée, `Ok(None)` si elle n'est pas dans le cache,
+    /// `CustomError::ValueCorrupted` si la valeur est corrompue, ou l'erreur de la source de données.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_try_get.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// assert_eq!(cache.try_get(&"pomme".to_string())?, Some(&1));
+    /// # std::fs::remove_file("cache_try_get.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_get(&mut self, key: &K) -> Result<Option<&V>, CustomError> {
+        if let Some(index) = self.live_position(key) {
+            self.verify_at(index)?;
+            return Ok(Some(self.hit_at(index)));
+        }
+        self.access_counters.misses += 1;
+        self.read_through(key)
+    }
+
+    /// Compte une lecture de l'élément à l'indice donné, le marque comme récemment utilisé et retourne sa valeur.
+    pub(crate) fn hit_at(&mut self, index: usize) -> &V {
+        self.access_counters.hits += 1;
+        self.cache[index].metadata.record_hit();
+        self.cache.move_to_back(index);
+        &self.cache[index].value
+    }
+
+    /// Retourne la valeur de l'élément à l'indice donné, sans vérifier son expiration.
+    pub(crate) fn value_at(&self, index: usize) -> &V {
+        &self.cache[index].value
+    }
+
+    /// Compare la valeur de l'élément à l'indice donné à sa somme de contrôle, si ce n'est pas déjà fait.
+    ///
+    /// Un élément corrompu est supprimé et `CustomError::ValueCorrupted` est retourné.
+    fn verify_at(&mut self, index: usize) -> Result<(), CustomError> {
+        let record = &mut self.cache[index];
+        if record.verified {
+            return Ok(());
+        }
+        if record.metadata.checksum.is_none_or(|checksum| value_checksum(&record.value) == Some(checksum)) {
+            record.verified = true;
+            return Ok(());
+        }
+
+        let record = self.remove_at(index);
+        for listener in self.listeners.iter() {
+            listener.on_corrupted(&record.key, &record.value);
+        }
+        Err(CustomError::ValueCorrupted)
+    }
+
+    /// Récupère les valeurs associées à plusieurs clés.
+    ///
+    /// Le résultat contient exactement un `BatchItem` par clé demandée, dans le même ordre que `keys`,
+    /// y compris pour les clés en double : il peut donc être parcouru avec `zip` sur les clés.
+    /// Chaque clé trouvée est traitée comme avec `get`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - Les clés à rechercher.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::{BatchStatus, CacheDB};
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_get_many.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// let keys = ["banane".to_string(), "pomme".to_string()];
+    /// let results = cache.get_many(&keys);
+    /// assert_eq!(results[0].status, BatchStatus::Missing);
+    /// assert_eq!(results[1].status, BatchStatus::Found(1));
+    /// # std::fs::remove_file("cache_get_many.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_many(&mut self, keys: &[K]) -> Vec<BatchItem<K, V>> {
+        keys.iter()
+            .map(|key| {
+                let status = match self.get(key) {
+                    Some(value) => BatchStatus::Found(value.clone()),
+                    None => BatchStatus::Missing,
+                };
+                BatchItem { key: key.clone(), status }
+            })
+            .collect()
+    }
+
+    /// Retourne les métadonnées de l'élément associé à une clé.
+    ///
+    /// Contrairement à `get`, la consultation des métadonnées ne modifie pas l'ordre d'utilisation
+    /// des éléments ni le compteur de lectures.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Some(EntryMetadata)` si la clé est dans le cache, ou `None` si elle est absente ou expirée.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_metadata.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.get(&"pomme".to_string());
+    ///
+    /// if let Some(metadata) = cache.metadata(&"pomme".to_string()) {
+    ///     assert_eq!(metadata.hit_count, 1);
+    ///     println!("pomme a été créée le {:?}", metadata.created_at);
+    /// }
+    /// # std::fs::remove_file("cache_metadata.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn metadata(&self, key: &K) -> Option<EntryMetadata> {
+        self.position(key).map(|index| self.cache[index].metadata).filter(|metadata| !metadata.is_expired())
+    }
+
+    /// Retourne les informations d'expiration de l'élément associé à une clé, comme la commande Redis `TTL`.
+    ///
+    /// La consultation ne modifie pas l'ordre d'utilisation des éléments. Un élément expiré qui
+    /// n'a pas encore été supprimé est considéré comme absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Some(TtlInfo)` si la clé est dans le cache, ou `None` sinon.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_ttl.txt")?;
+    /// cache.put_with_ttl("session".to_string(), 1, None, Some(Duration::from_secs(60)))?;
+    /// cache.put("pomme".to_string(), 2)?;
+    ///
+    /// let session = cache.ttl(&"session".to_string()).unwrap();
+    /// assert!(session.remaining.unwrap() <= Duration::from_secs(60));
+    /// assert_eq!(cache.ttl(&"pomme".to_string()).unwrap().remaining, None);
+    /// # std::fs::remove_file("cache_ttl.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ttl(&self, key: &K) -> Option<TtlInfo> {
+        let metadata = self.metadata(key)?;
+        Some(TtlInfo {
+            remaining: metadata.remaining_ttl(),
+            created_at: metadata.created_at,
+            expires_at: metadata.expires_at(),
+        })
+    }
+
+    /// Retourne les versions précédentes de la valeur associée à une clé, de la plus ancienne à la plus récente.
+    ///
+    /// La version courante n'en fait pas partie : son numéro est donné par `EntryMetadata::version`.
+    /// La consultation ne modifie pas l'ordre d'utilisation des éléments.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Retour
+    ///
+    /// Retourne les versions conservées, ou une liste vide si la clé n'est pas dans le cache.
+    pub fn history(&self, key: &K) -> &[ValueVersion<V>] {
+        self.position(key).map_or(&[], |index| &self.cache[index].history)
+    }
+
+    /// Rétablit une version précédente de la valeur associée à une clé.
+    ///
+    /// La valeur de cette version est réécrite comme avec `put` : elle reçoit donc un nouveau numéro
+    /// de version, et la valeur courante est ajoutée à l'historique.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé de l'élément.
+    /// * `version` - Le numéro de la version à rétablir.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si la version a été rétablie, `CustomError::NotFound` si la version n'est pas
+    /// dans l'historique de l'élément, `CustomError::Locked` si la clé est gelée, ou une autre erreur
+    /// `CustomError` si une erreur s'est produite.
+    pub fn rollback(&mut self, key: &K, version: u64) -> Result<(), CustomError> {
+        self.ensure_writable()?;
+        let value = match self.history(key).iter().find(|previous| previous.version == version) {
+            Some(previous) => previous.value.clone(),
+            None => return Err(CustomError::NotFound),
+        };
+        self.put(key.clone(), value)?;
+        Ok(())
+    }
+
+    /// Supprime l'élément associé à une clé du cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à supprimer.
+    ///
+    /// # Retour
+    ///
+    /// Retourne la valeur supprimée, `CustomError::NotFound` si la clé n'est pas dans le cache,
+    /// `CustomError::Locked` si la clé est gelée, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_remove.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// assert_eq!(cache.remove(&"pomme".to_string())?, 1);
+    /// # std::fs::remove_file("cache_remove.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove(&mut self, key: &K) -> Result<V, CustomError> {
+        self.timed("remove", |cache| {
+            cache.ensure_writable()?;
+            let index = match cache.live_position(key) {
+                Some(index) => index,
+                None => return Err(CustomError::NotFound),
+            };
+            if cache.cache[index].metadata.frozen {
+                return Err(CustomError::Locked);
+            }
+            let record = cache.remove_at(index);
+            for listener in cache.listeners.iter() {
+                listener.on_remove(&record.key, &record.value);
+            }
+            let value = record.value.clone();
+            cache.discard(record);
+            cache.log(&Operation::<&K, &V>::Remove { key })?;
+            cache.persist()?;
+            Ok(value)
+        })
+    }
+
+    /// Retire un élément de la mémoire et retourne sa valeur, pour le déplacer vers un autre cache.
+    ///
+    /// Contrairement à `remove`, les observateurs ne sont pas notifiés, l'élément n'est pas
+    /// conservé pendant le délai de grâce et le cache n'est pas persisté : le fichier est mis à
+    /// jour lors de la sauvegarde suivante. Retourne `None` si la clé est absente, expirée ou
+    /// corrompue, et `CustomError::Locked` si elle est gelée.
+    pub(crate) fn detach(&mut self, key: &K) -> Result<Option<V>, CustomError> {
+        self.ensure_writable()?;
+        let index = match self.live_position(key) {
+            Some(index) if self.verify_at(index).is_ok() => index,
+            _ => return Ok(None),
+        };
+        if self.cache[index].metadata.frozen {
+            return Err(CustomError::Locked);
+        }
+        Ok(Some(self.remove_at(index).value))
+    }
+
+    /// Supprime l'élément associé à une clé du cache et retourne sa valeur, s'il est présent.
+    ///
+    /// Contrairement à `remove`, une clé absente n'est pas une erreur : cette méthode permet de
+    /// consommer un élément qui a pu être retiré entre-temps.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à supprimer.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Some(V)` si l'élément a été supprimé, `None` si la clé n'est pas dans le cache,
+    /// `CustomError::Locked` si la clé est gelée, ou une autre erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_take.txt")?;
+    /// cache.put("tâche".to_string(), 1)?;
+    ///
+    /// assert_eq!(cache.take(&"tâche".to_string())?, Some(1));
+    /// assert_eq!(cache.take(&"tâche".to_string())?, None);
+    /// # std::fs::remove_file("cache_take.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn take(&mut self, key: &K) -> Result<Option<V>, CustomError> {
+        match self.remove(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(CustomError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Supprime l'élément le moins récemment utilisé et le retourne.
+    ///
+    /// Les éléments gelés sont ignorés, et les éléments expirés rencontrés sont supprimés comme
+    /// avec `get`. Un élément épinglé peut être retiré : l'épinglage ne protège que de l'éviction.
+    /// La suppression est enregistrée comme avec `remove`.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Some((K, V))` si un élément a été supprimé, `None` si aucun élément ne peut l'être,
+    /// ou une erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_pop_lru.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.put("banane".to_string(), 2)?;
+    ///
+    /// assert_eq!(cache.pop_lru()?, Some(("pomme".to_string(), 1)));
+    /// assert_eq!(cache.pop_mru()?, Some(("banane".to_string(), 2)));
+    /// assert_eq!(cache.pop_lru()?, None);
+    /// # std::fs::remove_file("cache_pop_lru.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pop_lru(&mut self) -> Result<Option<(K, V)>, CustomError> {
+        self.pop(false)
+    }
+
+    /// Supprime l'élément le plus récemment utilisé et le retourne.
+    ///
+    /// Voir `pop_lru` pour le traitement des éléments gelés, expirés et épinglés.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Some((K, V))` si un élément a été supprimé, `None` si aucun élément ne peut l'être,
+    /// ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn pop_mru(&mut self) -> Result<Option<(K, V)>, CustomError> {
+        self.pop(true)
+    }
+
+    /// Supprime le premier élément non gelé, en partant de l'élément le plus récemment utilisé si `mru` est vrai.
+    fn pop(&mut self, mru: bool) -> Result<Option<(K, V)>, CustomError> {
+        self.ensure_writable()?;
+        loop {
+            let poppable = |record: &&Record<K, V>| !record.metadata.frozen || record.metadata.is_expired();
+            let found = if mru {
+                self.cache.iter().rev().find(poppable)
+            } else {
+                self.cache.iter().find(poppable)
+            };
+            let key = match found {
+                Some(record) => record.key.clone(),
+                None => return Ok(None),
+            };
+            // Un élément expiré est supprimé par `remove`, qui retourne alors `NotFound`.
+            match self.remove(&key) {
+                Ok(value) => return Ok(Some((key, value))),
+                Err(CustomError::NotFound) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Supprime l'élément associé à une clé du cache, sans retourner la valeur supprimée.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si la clé a été trouvée et supprimée, ou une erreur `CustomError` comme `remove`.
+    #[deprecated(note = "utilisez `remove`, qui retourne la valeur supprimée")]
+    pub fn delete(&mut self, key: &K) -> Result<(), CustomError> {
+        self.remove(key).map(|_| ())
+    }
+
+    /// Vide le cache.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nombre d'éléments supprimés, y compris les éléments expirés pas encore purgés et
+    /// les éléments conservés par la suppression réversible, ou une erreur `CustomError` si une
+    /// erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_clear.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// assert_eq!(cache.clear()?, 1);
+    /// assert_eq!(cache.len(), 0);
+    /// # std::fs::remove_file("cache_clear.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clear(&mut self) -> Result<usize, CustomError> {
+        self.timed("clear", |cache| {
+            cache.ensure_writable()?;
+            for record in cache.cache.iter() {
+                for listener in cache.listeners.iter() {
+                    listener.on_remove(&record.key, &record.value);
+                }
+            }
+            let count = cache.cache.len() + cache.tombstones.len();
+            cache.clear_entries();
+            cache.log(&Operation::<&K, &V>::Clear)?;
+            cache.persist()?;
+            Ok(count)
+        })
+    }
+
+    /// Conserve uniquement les éléments pour lesquels le prédicat retourne `true`, puis persiste
+    /// le cache une seule fois.
+    ///
+    /// Les éléments gelés sont toujours conservés, sans appeler le prédicat. Chaque suppression
+    /// est notifiée avec `on_remove` et enregistrée dans le journal d'opérations comme avec `remove`.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Le prédicat appliqué à chaque clé et valeur.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nombre d'éléments supprimés, ou une erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_retain.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.put("banane".to_string(), 2)?;
+    ///
+    /// assert_eq!(cache.retain(|_, value| *value > 1)?, 1);
+    /// assert_eq!(cache.len(), 1);
+    /// # std::fs::remove_file("cache_retain.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F) -> Result<usize, CustomError>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.ensure_writable()?;
+        let removed: Vec<K> = self
+            .cache
+            .iter()
+            .filter(|record| !record.metadata.frozen && !f(&record.key, &record.value))
+            .map(|record| record.key.clone())
+            .collect();
+
+        for key in removed.iter() {
+            if let Some(index) = self.position(key) {
+                let record = self.remove_at(index);
+                for listener in self.listeners.iter() {
+                    listener.on_remove(&record.key, &record.value);
+                }
+                self.discard(record);
+                self.log(&Operation::<&K, &V>::Remove { key })?;
+            }
+        }
+        if !removed.is_empty() {
+            self.persist()?;
+        }
+        Ok(removed.len())
+    }
+
+    /// Supprime tous les éléments dont la clé commence par le préfixe donné, comme avec `retain`.
+    ///
+    /// Permet d'invalider en une seule sauvegarde toute une famille de clés, par exemple `user:42:`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Le préfixe des clés à supprimer, comparé à leur représentation textuelle.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nombre d'éléments supprimés, ou une erreur `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_remove_prefix.txt")?;
+    /// cache.put("user:42:profil".to_string(), 1)?;
+    /// cache.put("user:42:panier".to_string(), 2)?;
+    /// cache.put("user:7:profil".to_string(), 3)?;
+    ///
+    /// assert_eq!(cache.remove_prefix("user:42:")?, 2);
+    /// assert_eq!(cache.len(), 1);
+    /// # std::fs::remove_file("cache_remove_prefix.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove_prefix(&mut self, prefix: &str) -> Result<usize, CustomError> {
+        self.retain(|key, _| !key.to_string().starts_with(prefix))
+    }
+
+    /// Applique plusieurs modifications ensemble : toutes sont appliquées, ou aucune.
+    ///
+    /// La closure enregistre les modifications dans une `Transaction`. Elles sont ensuite appliquées
+    /// dans leur ordre d'enregistrement, puis le cache est persisté une seule fois. Si une modification
+    /// échoue, le contenu du cache est rétabli tel qu'il était avant la transaction et l'erreur est
+    /// retournée. Les observateurs et le journal d'opérations ne sont informés qu'une fois la
+    /// transaction réussie.
+    ///
+    /// Les écritures déjà faites dans la source de données éventuelle ne sont pas annulées.
+    ///
+    /// # Arguments
+    ///
+    /// * `build` - La closure qui enregistre les modifications.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si toutes les modifications ont été appliquées, ou l'erreur `CustomError` de
+    /// la première modification qui a échoué.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_transaction.txt")?;
+    /// cache.put("compte:a".to_string(), 100)?;
+    ///
+    /// cache.transaction(|tx| {
+    ///     tx.put("compte:b".to_string(), 100).remove("compte:a".to_string());
+    /// })?;
+    /// assert_eq!(cache.len(), 1);
+    ///
+    /// // "compte:c" est absent : la transaction échoue et "compte:b" est conservé
+    /// let result = cache.transaction(|tx| {
+    ///     tx.remove("compte:b".to_string()).remove("compte:c".to_string());
+    /// });
+    /// assert!(matches!(result, Err(CustomError::NotFound)));
+    /// assert!(cache.contains_key(&"compte:b".to_string()));
+    /// # std::fs::remove_file("cache_transaction.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transaction<F>(&mut self, build: F) -> Result<(), CustomError>
+    where
+        F: FnOnce(&mut Transaction<K, V>),
+    {
+        self.ensure_writable()?;
+        let mut transaction = Transaction::new();
+        build(&mut transaction);
+        if transaction.is_empty() {
+            return Ok(());
+        }
+
+        let before = self.cache.clone();
+        let (used_bytes, tombstones, access_counters) = (self.used_bytes, self.tombstones.clone(), self.access_counters);
+        let listeners = std::mem::take(&mut self.listeners);
+        let applied = self.apply_transaction(&transaction.operations);
+        self.listeners = listeners;
+
+        let mut removed_values = match applied {
+            Ok(removed_values) => removed_values.into_iter(),
+            Err(e) => {
+                self.cache = before;
+                self.used_bytes = used_bytes;
+                self.tombstones = tombstones;
+                self.access_counters = access_counters;
+                return Err(e);
+            }
+        };
+
+        let mut removed_keys = HashSet::new();
+        for operation in transaction.operations.iter() {
+            match operation {
+                TransactionOperation::Put { key, value } => {
+                    for listener in self.listeners.iter() {
+                        listener.on_insert(key, value);
+                    }
+                    self.log(&Operation::Put { key, value })?;
+                }
+                TransactionOperation::Remove { key } => {
+                    if let Some(value) = removed_values.next() {
+                        for listener in self.listeners.iter() {
+                            listener.on_remove(key, &value);
+                        }
+                    }
+                    removed_keys.insert(key);
+                    self.log(&Operation::<&K, &V>::Remove { key })?;
+                }
+            }
+        }
+        // Les éléments disparus sans être supprimés par la transaction ont expiré ou ont été évincés.
+        for record in before.iter().filter(|record| !removed_keys.contains(&record.key) && self.position(&record.key).is_none()) {
+            for listener in self.listeners.iter() {
+                if record.metadata.is_expired() {
+                    listener.on_expire(&record.key, &record.value);
+                } else {
+                    listener.on_evict(&record.key, &record.value);
+                }
+            }
+        }
+
+        self.persist()
+    }
+
+    /// Applique les modifications d'une transaction en mémoire, sans persister le cache.
+    ///
+    /// Retourne les valeurs supprimées, dans l'ordre des suppressions.
+    fn apply_transaction(&mut self, operations: &[TransactionOperation<K, V>]) -> Result<Vec<V>, CustomError> {
+        let mut removed_values = Vec::new();
+        for operation in operations {
+            match operation {
+                TransactionOperation::Put { key, value } => {
+                    self.write_through(key, value)?;
+                    self.insert(key.clone(), value.clone())?;
+                }
+                TransactionOperation::Remove { key } => {
+                    let index = match self.live_position(key) {
+                        Some(index) => index,
+                        None => return Err(CustomError::NotFound),
+                    };
+                    if self.cache[index].metadata.frozen {
+                        return Err(CustomError::Locked);
+                    }
+                    let record = self.remove_at(index);
+                    removed_values.push(record.value.clone());
+                    self.discard(record);
+                }
+            }
+        }
+        Ok(removed_values)
+    }
+
+    /// Retourne un itérateur sur les éléments du cache.
+    ///
+    /// Les éléments sont parcourus du moins récemment utilisé au plus récemment utilisé. Comme
+    /// toutes les lectures, le parcours ignore les éléments expirés qui n'ont pas encore été
+    /// supprimés. L'itérateur emprunte le cache : aucune modification ne peut avoir lieu pendant le parcours,
+    /// qui porte donc toujours sur un état cohérent. Pour conserver une copie indépendante du
+    /// cache, utilisez `snapshot`.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_iter.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.put("banane".to_string(), 2)?;
+    ///
+    /// for (key, value) in cache.iter() {
+    ///     println!("Clé: {}, Valeur: {}", key, value);
+    /// }
+    /// # std::fs::remove_file("cache_iter.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.cache.iter() }
+    }
+
+    /// Recherche les éléments dont la clé commence par le préfixe donné.
+    ///
+    /// La recherche ne modifie pas l'ordre d'utilisation des éléments.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Le préfixe à rechercher dans la représentation textuelle des clés.
+    ///
+    /// # Retour
+    ///
+    /// Retourne la liste des éléments trouvés sous la forme `(position, clé, valeur)`,
+    /// où la position est l'indice de l'élément dans l'ordre de `iter()`.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_scan_prefix.txt")?;
+    /// cache.put("user:1".to_string(), 1)?;
+    /// cache.put("pomme".to_string(), 2)?;
+    /// cache.put("user:2".to_string(), 3)?;
+    ///
+    /// for (position, key, value) in cache.scan_prefix("user:") {
+    ///     println!("{}: {} = {}", position, key, value);
+    /// }
+    /// # std::fs::remove_file("cache_scan_prefix.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scan_prefix(&self, prefix: &str) -> Vec<(usize, &K, &V)> {
+        self.live_records()
+            .enumerate()
+            .filter(|(_, record)| record.key.to_string().starts_with(prefix))
+            .map(|(position, record)| (position, &record.key, &record.value))
+            .collect()
+    }
+
+    /// Retourne l'élément à la position donnée, dans l'ordre de `iter()`.
+    ///
+    /// La position 0 correspond au prochain élément à évincer. La lecture ne modifie pas l'ordre
+    /// d'utilisation des éléments.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - La position de l'élément.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Some((clé, valeur))` si la position existe, ou `None` sinon.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_peek_at.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.put("banane".to_string(), 2)?;
+    ///
+    /// assert_eq!(cache.peek_at(0), Some((&"pomme".to_string(), &1)));
+    /// assert_eq!(cache.peek_at(2), None);
+    /// # std::fs::remove_file("cache_peek_at.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn peek_at(&self, position: usize) -> Option<(&K, &V)> {
+        self.live_records().nth(position).map(|record| (&record.key, &record.value))
+    }
+
+    /// Parcourt les éléments non expirés, dans l'ordre de `iter()`.
+    fn live_records(&self) -> impl Iterator<Item = &Record<K, V>> {
+        self.cache.iter().filter(|record| !record.metadata.is_expired())
+    }
+
+    /// Évince l'élément à la position donnée, dans l'ordre de `iter()`, puis persiste le cache.
+    ///
+    /// Les observateurs sont notifiés avec `on_evict`, et la suppression est enregistrée dans le
+    /// journal d'opérations comme avec `remove`.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - La position de l'élément.
+    ///
+    /// # Retour
+    ///
+    /// Retourne la clé et la valeur évincées, `CustomError::NotFound` si la position n'existe pas,
+    /// `CustomError::Locked` si l'élément est gelé ou épinglé, ou une autre erreur `CustomError` si une erreur s'est produite.
+    pub fn evict_at(&mut self, position: usize) -> Result<(K, V), CustomError> {
+        self.ensure_writable()?;
+        let index = match self.live_records().nth(position) {
+            Some(record) if record.metadata.frozen || record.metadata.pinned => return Err(CustomError::Locked),
+            Some(record) => self.position(&record.key),
+            None => None,
+        };
+        let record = match index {
+            Some(index) => self.remove_at(index),
+            None => return Err(CustomError::NotFound),
+        };
+        self.access_counters.evictions += 1;
+        for listener in self.listeners.iter() {
+            listener.on_evict(&record.key, &record.value);
+        }
+
+        self.log(&Operation::<&K, &V>::Remove { key: &record.key })?;
+        self.persist()?;
+        Ok((record.key, record.value))
+    }
+
+    /// Retourne le nombre d'éléments dans le cache.
+    ///
+    /// Les éléments expirés qui n'ont pas encore été supprimés ne sont pas comptés ; le comptage
+    /// parcourt donc tous les éléments.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_len.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// assert_eq!(cache.len(), 1);
+    /// # std::fs::remove_file("cache_len.txt")?;
     /// # Ok(())
     /// # }
     /// ```
     pub fn len(&self) -> usize {
-        self.cache.len()
+        self.live_records().count()
+    }
+
+    /// Indique si le cache est vide.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let cache = CacheDB::<String, i32>::new_persistent(5, "cache_is_empty.txt")?;
+    /// assert!(cache.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.live_records().next().is_none()
+    }
+
+    /// Retourne le nombre maximal d'éléments du cache.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let cache = CacheDB::<String, i32>::new_persistent(5, "cache_capacity.txt")?;
+    /// assert_eq!(cache.capacity(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Modifie la capacité du cache sans le recharger.
+    ///
+    /// Lorsque la nouvelle capacité est inférieure au nombre d'éléments, la politique de capacité
+    /// du cache s'applique : `TruncateLru` évince les éléments non épinglés les moins récemment
+    /// utilisés, `Error` refuse la modification et `Grow` limite la réduction au nombre d'éléments.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_capacity` - La nouvelle capacité maximale du cache.
+    ///
+    /// # Retour
+    ///
+    /// Retourne le nombre d'éléments évincés, `CustomError::CacheDbCapacityError` si la réduction est
+    /// refusée avec `CapacityPolicy::Error` ou si trop d'éléments sont épinglés, ou une autre erreur
+    /// `CustomError` si une erreur s'est produite.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_resize.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.put("banane".to_string(), 2)?;
+    ///
+    /// assert_eq!(cache.resize(1)?, 1); // "pomme" est évincé
+    /// assert_eq!(cache.capacity(), 1);
+    /// # std::fs::remove_file("cache_resize.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resize(&mut self, new_capacity: usize) -> Result<usize, CustomError> {
+        self.ensure_writable()?;
+        let new_capacity = match self.capacity_policy {
+            CapacityPolicy::Error if self.cache.len() > new_capacity => return Err(CustomError::CacheDbCapacityError),
+            CapacityPolicy::Grow => new_capacity.max(self.cache.len()),
+            _ => new_capacity,
+        };
+
+        let evicted = self.cache.len().saturating_sub(new_capacity);
+        if self.cache.iter().filter(|record| !record.metadata.pinned).count() < evicted {
+            return Err(CustomError::CacheDbCapacityError);
+        }
+
+        self.capacity = new_capacity;
+        for _ in 0..evicted {
+            self.evict_lru()?;
+        }
+        if evicted > 0 {
+            self.persist()?;
+        }
+        Ok(evicted)
+    }
+
+    /// Indique si une clé est dans le cache, sans modifier l'ordre d'utilisation des éléments.
+    ///
+    /// Un élément dont la durée de vie stricte est écoulée est considéré comme absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_contains_key.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// assert!(cache.contains_key(&"pomme".to_string()));
+    /// assert!(!cache.contains_key(&"banane".to_string()));
+    /// # std::fs::remove_file("cache_contains_key.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.position(key).is_some_and(|index| !self.cache[index].metadata.is_expired())
+    }
+
+    /// Lit la valeur associée à une clé sans modifier l'ordre d'utilisation des éléments.
+    ///
+    /// Contrairement à `get`, l'élément n'est pas promu, son compteur de lectures n'est pas
+    /// incrémenté et la source de données n'est pas consultée en cas d'absence.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Some(&V)` si la clé est trouvée, ou `None` si elle est absente, expirée ou si sa
+    /// valeur ne correspond plus à sa somme de contrôle.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(2, "cache_peek.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    /// cache.put("banane".to_string(), 2)?;
+    ///
+    /// assert_eq!(cache.peek(&"pomme".to_string()), Some(&1));
+    /// cache.put("orange".to_string(), 3)?; // "pomme" reste le moins récemment utilisé
+    /// assert_eq!(cache.peek(&"pomme".to_string()), None);
+    /// # std::fs::remove_file("cache_peek.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let record = &self.cache[self.position(key)?];
+        if record.metadata.is_expired() {
+            return None;
+        }
+        if !record.verified && record.metadata.checksum.is_some_and(|checksum| value_checksum(&record.value) != Some(checksum)) {
+            return None;
+        }
+        Some(&record.value)
+    }
+
+    /// Retourne les statistiques d'utilisation du cache.
+    ///
+    /// Les statistiques incluent la durée des sauvegardes du fichier et sa taille après la dernière
+    /// sauvegarde ; les écritures faites par `snapshot` ne sont pas comptées. Les lectures sont
+    /// comptées par `get` et les méthodes qui s'appuient dessus, mais pas par `peek`.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_stats.txt")?;
+    /// cache.put("pomme".to_string(), 1)?;
+    ///
+    /// let stats = cache.stats();
+    /// assert_eq!(stats.len, 1);
+    /// assert_eq!(stats.capacity, 5);
+    /// assert_eq!(stats.used_bytes, 8);
+    ///
+    /// cache.get(&"pomme".to_string());
+    /// cache.get(&"banane".to_string());
+    /// assert_eq!(cache.stats().hit_ratio(), Some(0.5));
+    /// # std::fs::remove_file("cache_stats.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stats(&self) -> CacheStats {
+        let metrics = self.persist_metrics.get();
+        CacheStats {
+            len: self.cache.len(),
+            capacity: self.capacity,
+            used_bytes: self.used_bytes,
+            max_bytes: self.max_bytes,
+            persist_count: metrics.count,
+            last_persist_duration: metrics.last_duration,
+            total_persist_duration: metrics.total_duration,
+            file_size: metrics.file_size,
+            last_persist_at: metrics.last_at,
+            hits: self.access_counters.hits,
+            misses: self.access_counters.misses,
+            evictions: self.access_counters.evictions,
+            uptime: self.opened_at.elapsed(),
+            listeners: self.listeners.len(),
+        }
+    }
+
+    /// Retourne l'historique des migrations appliquées au fichier du cache.
+    ///
+    /// Les migrations du format de fichier sont appliquées automatiquement au chargement ;
+    /// un cache créé sans fichier existant est directement dans la version courante du format.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use eval_rust::CacheDB;
+    /// use eval_rust::errors::CustomError;
+    ///
+    /// # fn main() -> Result<(), CustomError> {
+    /// let cache = CacheDB::<String, i32>::new_persistent(5, "cache_migration_history.txt")?;
+    ///
+    /// for migration in cache.migration_history() {
+    ///     println!("{} {} ({:?})", migration.version, migration.name, migration.applied_at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn migration_history(&self) -> &[MigrationRecord] {
+        &self.migration_history
+    }
+}
+
+/// Contenu d'un fichier de cache : ses éléments, l'historique de ses migrations, ses en-têtes inconnus
+/// et les lignes ignorées lors d'une lecture tolérante.
+struct FileContents<K, V> {
+    entries: Vec<Record<K, V>>,
+    history: Vec<MigrationRecord>,
+    extra_headers: Vec<String>,
+    skipped: Vec<SkippedLine>,
+}
+
+/// Retourne la taille d'un élément, c'est-à-dire celle de sa clé et de sa valeur sérialisées en JSON.
+fn entry_size<K: Serialize, V: Serialize>(key: &K, value: &V) -> usize {
+    let key_size = serde_json::to_string(key).map_or(0, |json| json.len());
+    let value_size = serde_json::to_string(value).map_or(0, |json| json.len());
+    key_size + value_size
+}
+
+impl<'a, K, V> IntoIterator for &'a CacheDB<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<K, V> IntoIterator for CacheDB<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Consomme le cache et retourne ses éléments, du moins au plus récemment utilisé.
+    ///
+    /// Les modifications en attente de la persistance différée sont sauvegardées avant ; le
+    /// fichier du cache n'est pas modifié ensuite.
+    fn into_iter(mut self) -> IntoIter<K, V> {
+        if self.write_behind.as_ref().is_some_and(WriteBehind::is_dirty) {
+            let _ = self.save();
+        }
+        self.write_behind = None;
+        let inner = std::mem::replace(&mut self.cache, LruList::with_capacity(0));
+        IntoIter { inner }
+    }
+}
+
+impl<K, V> Extend<(K, V)> for CacheDB<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Insère les paires clé-valeur comme avec `put`, en persistant le cache une seule fois.
+    ///
+    /// Les paires refusées, par exemple parce que leur clé est gelée, sont ignorées ; utilisez
+    /// `put` pour obtenir les erreurs.
+    ///
+    /// # Panics
+    ///
+    /// Panique si le cache est ouvert en lecture seule avec `with_file_lock`, plutôt que
+    /// d'ignorer toutes les paires sans le signaler.
+    ///
+    /// # Note
+    ///
+    /// `FromIterator` n'est pas implémenté : un cache a besoin d'une capacité et d'un fichier, que
+    /// `from_iter` ne peut pas recevoir. Créez le cache avec `new_persistent`, puis utilisez `extend`.
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        if self.ensure_writable().is_err() {
+            panic!("impossible d'étendre un cache ouvert en lecture seule");
+        }
+        for (key, value) in iter {
+            if self.write_through(&key, &value).is_ok() && self.insert(key, value).is_ok() {
+                let _ = self.log_last_put();
+            }
+        }
+        let _ = self.persist();
     }
-}
\ No newline at end of file
+}
+
+impl<K, V> fmt::Debug for CacheDB<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de> + fmt::Debug,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut entries = f.debug_map();
+        for record in self.cache.iter() {
+            if self.redact_debug {
+                entries.entry(&record.key, &format_args!("<masqué>"));
+            } else {
+                entries.entry(&record.key, &record.value);
+            }
+        }
+        entries.finish()
+    }
+}
+
+impl<K, V> Drop for CacheDB<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    fn drop(&mut self) {
+        if self.write_behind.as_ref().is_some_and(WriteBehind::is_dirty) {
+            let _ = self.save();
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use crate::lru::{LruList, Records};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::hash::Hash;
+use std::time::{Duration, SystemTime};
+
+/// Métadonnées associées à un élément du cache.
+///
+/// Elles sont persistées avec l'élément et retournées par `CacheDB::metadata`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryMetadata {
+    /// La date d'insertion de la clé dans le cache.
+    pub created_at: SystemTime,
+    /// La date de la dernière modification de la valeur.
+    pub updated_at: SystemTime,
+    /// La date du dernier accès à l'élément, en lecture ou en écriture.
+    pub last_accessed: SystemTime,
+    /// Le nombre de lectures réussies de l'élément.
+    pub hit_count: u64,
+    /// Le numéro de version de la valeur, qui commence à 1 et augmente à chaque modification.
+    #[serde(default)]
+    pub version: u64,
+    /// Indique si l'élément est gelé : ses modifications et sa suppression sont refusées.
+    #[serde(default)]
+    pub frozen: bool,
+    /// Indique si l'élément est épinglé : il n'est jamais évincé pour respecter la capacité du cache.
+    #[serde(default)]
+    pub pinned: bool,
+    /// La durée de vie après laquelle l'élément est signalé comme périmé, ou `None`.
+    #[serde(default)]
+    pub soft_ttl: Option<Duration>,
+    /// La durée de vie après laquelle l'élément expire et est supprimé, ou `None`.
+    #[serde(default)]
+    pub hard_ttl: Option<Duration>,
+    /// La date à partir de laquelle les durées de vie sont décomptées : la dernière modification
+    /// de la valeur ou le dernier appel à `CacheDB::expire`.
+    #[serde(default = "SystemTime::now")]
+    pub ttl_started_at: SystemTime,
+    /// La somme de contrôle CRC32 de la valeur encodée en JSON, vérifiée à la lecture de l'élément.
+    #[serde(default)]
+    pub checksum: Option<u32>,
+    /// La date de suppression de l'élément, s'il est conservé comme élément supprimé par `CacheDB::with_soft_delete`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<SystemTime>,
+}
+
+impl EntryMetadata {
+    pub(crate) fn new() -> Self {
+        let now = SystemTime::now();
+        EntryMetadata {
+            created_at: now,
+            updated_at: now,
+            last_accessed: now,
+            hit_count: 0,
+            version: 1,
+            frozen: false,
+            pinned: false,
+            soft_ttl: None,
+            hard_ttl: None,
+            ttl_started_at: now,
+            checksum: None,
+            deleted_at: None,
+        }
+    }
+
+    /// Retourne la date à partir de laquelle l'élément est périmé, ou `None` s'il n'a pas de durée de vie souple.
+    pub fn stale_at(&self) -> Option<SystemTime> {
+        self.soft_ttl.and_then(|ttl| self.ttl_started_at.checked_add(ttl))
+    }
+
+    /// Retourne la date à laquelle l'élément expire, ou `None` s'il n'a pas de durée de vie stricte.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.hard_ttl.and_then(|ttl| self.ttl_started_at.checked_add(ttl))
+    }
+
+    /// Indique si la durée de vie souple de l'élément est écoulée.
+    ///
+    /// Un élément périmé reste lisible, mais il est conseillé de rafraîchir sa valeur.
+    pub fn is_stale(&self) -> bool {
+        self.stale_at().is_some_and(|stale_at| stale_at <= SystemTime::now())
+    }
+
+    /// Indique si la durée de vie stricte de l'élément est écoulée.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at().is_some_and(|expires_at| expires_at <= SystemTime::now())
+    }
+
+    /// Retourne la durée restante avant l'expiration de l'élément, ou `None` s'il n'a pas de durée de vie stricte.
+    ///
+    /// Retourne `Duration::ZERO` si l'élément a déjà expiré.
+    pub fn remaining_ttl(&self) -> Option<Duration> {
+        self.expires_at()
+            .map(|expires_at| expires_at.duration_since(SystemTime::now()).unwrap_or_default())
+    }
+
+    /// Définit les durées de vie de l'élément et relance leur décompte.
+    pub(crate) fn set_ttl(&mut self, soft_ttl: Option<Duration>, hard_ttl: Option<Duration>) {
+        self.soft_ttl = soft_ttl;
+        self.hard_ttl = hard_ttl;
+        self.ttl_started_at = SystemTime::now();
+    }
+
+    /// Met à jour les métadonnées après une modification de la valeur, et relance le décompte des durées de vie.
+    pub(crate) fn record_update(&mut self) {
+        let now = SystemTime::now();
+        self.updated_at = now;
+        self.last_accessed = now;
+        self.ttl_started_at = now;
+        self.version += 1;
+    }
+
+    /// Met à jour les métadonnées après une lecture de la valeur.
+    pub(crate) fn record_hit(&mut self) {
+        self.last_accessed = SystemTime::now();
+        self.hit_count += 1;
+    }
+
+    /// Met à jour les métadonnées après un accès sans lecture, et relance le décompte des durées de vie
+    /// s'il y en a et si l'élément n'est pas gelé.
+    pub(crate) fn record_touch(&mut self) {
+        let now = SystemTime::now();
+        self.last_accessed = now;
+        if !self.frozen && (self.soft_ttl.is_some() || self.hard_ttl.is_some()) {
+            self.ttl_started_at = now;
+        }
+    }
+}
+
+/// Informations d'expiration d'un élément, retournées par `CacheDB::ttl`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TtlInfo {
+    /// La durée restante avant l'expiration de l'élément, ou `None` s'il n'expire pas.
+    pub remaining: Option<Duration>,
+    /// La date d'insertion de la clé dans le cache.
+    pub created_at: SystemTime,
+    /// La date à laquelle l'élément expire, ou `None` s'il n'expire pas.
+    pub expires_at: Option<SystemTime>,
+}
+
+/// Version précédente de la valeur d'un élément, retournée par `CacheDB::history`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueVersion<V> {
+    /// Le numéro de version de la valeur.
+    pub version: u64,
+    /// La valeur.
+    pub value: V,
+    /// La date à laquelle la valeur a été écrite.
+    pub updated_at: SystemTime,
+}
+
+/// Élément stocké dans le cache : une paire clé-valeur et ses métadonnées.
+#[derive(Debug, Clone)]
+pub(crate) struct Record<K, V> {
+    pub(crate) key: K,
+    pub(crate) value: V,
+    pub(crate) metadata: EntryMetadata,
+    pub(crate) extensions: Extensions,
+    /// Les versions précédentes de la valeur, de la plus ancienne à la plus récente.
+    pub(crate) history: Vec<ValueVersion<V>>,
+    /// Indique si la valeur a déjà été comparée à sa somme de contrôle depuis son chargement.
+    pub(crate) verified: bool,
+}
+
+/// Données d'un élément écrites par une version plus récente et inconnues de cette version.
+///
+/// Elles sont conservées telles quelles et réécrites lors de la sauvegarde, afin qu'un retour
+/// à une version précédente ne les efface pas.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Extensions {
+    /// Les champs inconnus des métadonnées.
+    pub(crate) metadata: Map<String, Value>,
+    /// Les champs JSON suivant les métadonnées sur la ligne de l'élément.
+    pub(crate) fields: Vec<String>,
+}
+
+/// Lit les métadonnées d'un élément, en séparant les champs inconnus de cette version.
+pub(crate) fn parse_metadata(json: &str) -> Option<(EntryMetadata, Map<String, Value>)> {
+    let mut fields = match serde_json::from_str(json) {
+        Ok(Value::Object(fields)) => fields,
+        _ => return None,
+    };
+
+    let metadata: EntryMetadata = serde_json::from_value(Value::Object(fields.clone())).ok()?;
+    if let Ok(Value::Object(known)) = serde_json::to_value(metadata) {
+        fields.retain(|name, _| !known.contains_key(name));
+    }
+
+    Some((metadata, fields))
+}
+
+/// Nom du champ des métadonnées dans lequel les versions précédentes de la valeur sont persistées.
+const HISTORY_FIELD: &str = "history";
+
+/// Encode les métadonnées d'un élément en JSON, en y ajoutant les champs inconnus conservés.
+fn metadata_to_json(metadata: &EntryMetadata, unknown: &Map<String, Value>) -> Result<String, serde_json::Error> {
+    let mut value = serde_json::to_value(metadata)?;
+    if let Value::Object(fields) = &mut value {
+        for (name, field) in unknown {
+            fields.entry(name.clone()).or_insert_with(|| field.clone());
+        }
+    }
+    serde_json::to_string(&value)
+}
+
+/// Encode les métadonnées d'un élément en JSON, avec les versions précédentes de sa valeur et les champs inconnus conservés.
+pub(crate) fn record_metadata_to_json<K, V: Serialize>(record: &Record<K, V>) -> Result<String, serde_json::Error> {
+    if record.history.is_empty() {
+        return metadata_to_json(&record.metadata, &record.extensions.metadata);
+    }
+    let mut fields = record.extensions.metadata.clone();
+    fields.insert(HISTORY_FIELD.to_string(), serde_json::to_value(&record.history)?);
+    metadata_to_json(&record.metadata, &fields)
+}
+
+/// Retire des champs inconnus des métadonnées les versions précédentes de la valeur.
+///
+/// Retourne `None` si les versions sont illisibles.
+pub(crate) fn take_history<V: for<'de> Deserialize<'de>>(unknown: &mut Map<String, Value>) -> Option<Vec<ValueVersion<V>>> {
+    match unknown.remove(HISTORY_FIELD) {
+        Some(history) => serde_json::from_value(history).ok(),
+        None => Some(Vec::new()),
+    }
+}
+
+/// Itérateur sur les paires clé-valeur d'un cache, retourné par `CacheDB::iter`.
+///
+/// Les éléments expirés qui n'ont pas encore été supprimés sont ignorés.
+pub struct Iter<'a, K, V> {
+    pub(crate) inner: Records<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.find(|record| !record.metadata.is_expired()).map(|record| (&record.key, &record.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.rfind(|record| !record.metadata.is_expired()).map(|record| (&record.key, &record.value))
+    }
+}
+
+/// Itérateur sur les paires clé-valeur d'un cache consommé, retourné par `CacheDB::into_iter`.
+///
+/// Les éléments sont retournés du moins au plus récemment utilisé. Les éléments expirés qui
+/// n'ont pas encore été supprimés sont ignorés.
+pub struct IntoIter<K, V> {
+    pub(crate) inner: LruList<K, V>,
+}
+
+impl<K: Eq + Hash + Clone, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        std::iter::from_fn(|| self.inner.pop_front())
+            .find(|record| !record.metadata.is_expired())
+            .map(|record| (record.key, record.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.inner.len()))
+    }
+}
+
+impl<K: Eq + Hash + Clone, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        std::iter::from_fn(|| self.inner.pop_back())
+            .find(|record| !record.metadata.is_expired())
+            .map(|record| (record.key, record.value))
+    }
+}
+
+/// Découpe une ligne du fichier de cache en champs JSON séparés par `=`.
+///
+/// Les `=` contenus dans les chaînes, objets ou tableaux JSON ne sont pas considérés comme des séparateurs.
+pub(crate) fn split_line(line: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in line.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth = depth.saturating_sub(1),
+            '=' if depth == 0 => {
+                fields.push(&line[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    fields.push(&line[start..]);
+    fields
+}
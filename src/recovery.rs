@@ -0,0 +1,26 @@
+use crate::errors::CustomError;
+
+/// Ligne du fichier d'un cache ignorée lors d'un chargement tolérant.
+#[derive(Debug)]
+pub struct SkippedLine {
+    /// Le numéro de la ligne dans le fichier, à partir de 1.
+    pub line: usize,
+    /// Le contenu brut de la ligne.
+    pub content: String,
+    /// L'erreur qui aurait interrompu un chargement strict.
+    pub error: CustomError,
+}
+
+/// Rapport d'un chargement tolérant, retourné par `CacheDB::load_lenient`.
+#[derive(Debug, Default)]
+pub struct RecoveryReport {
+    /// Les lignes ignorées, dans l'ordre du fichier.
+    pub skipped: Vec<SkippedLine>,
+}
+
+impl RecoveryReport {
+    /// Indique si toutes les lignes du fichier ont pu être chargées.
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
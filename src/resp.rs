@@ -0,0 +1,473 @@
+use crate::batch::BatchStatus;
+use crate::errors::CustomError;
+use crate::persistent::CacheDB;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Cache partagé entre les connexions du serveur RESP.
+pub type SharedCache = Arc<Mutex<CacheDB<String, String>>>;
+
+/// Nombre maximal d'arguments d'une commande, comme la limite de Redis.
+const MAX_MULTIBULK_LENGTH: usize = 1024 * 1024;
+
+/// Taille maximale d'un argument, comme `proto-max-bulk-len` dans Redis.
+const MAX_BULK_LENGTH: usize = 512 * 1024 * 1024;
+
+/// Taille maximale d'une ligne, terminateur compris, comme la limite des commandes en ligne de Redis.
+const MAX_INLINE_LENGTH: usize = 64 * 1024;
+
+/// Sert un cache avec un sous-ensemble du protocole Redis (RESP).
+///
+/// Chaque connexion est traitée dans son propre thread. Les commandes prises en charge sont
+/// `PING`, `GET`, `MGET`, `SET`, `SETNX`, `DEL`, `EXPIRE`, `TOUCH`, `TTL`, `KEYS`, `PUBLISH`, `SUBSCRIBE`
+/// et `QUIT`, envoyées sous forme de tableau RESP ou de commande en ligne. `EXPIRE` définit la durée
+/// de vie stricte de l'élément. Cette fonction ne retourne qu'en cas d'erreur du `TcpListener`.
+///
+/// `PUBLISH` et `SUBSCRIBE` permettent aux clients d'échanger des messages, par exemple pour
+/// diffuser des invalidations entre plusieurs instances d'une application. Les messages ne sont
+/// pas stockés dans le cache : seuls les abonnés connectés les reçoivent. Une connexion abonnée
+/// ne fait plus que recevoir des messages : les commandes qu'elle envoie sont ignorées, et son
+/// abonnement prend fin à sa fermeture.
+///
+/// Si un thread panique en détenant le verrou du cache, les connexions continuent d'utiliser le
+/// cache dans l'état où il l'a laissé.
+///
+/// # Arguments
+///
+/// * `listener` - Le socket sur lequel accepter les connexions.
+/// * `cache` - Le cache partagé par toutes les connexions.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use eval_rust::CacheDB;
+/// use eval_rust::errors::CustomError;
+/// use eval_rust::resp;
+/// use std::net::TcpListener;
+/// use std::sync::{Arc, Mutex, Weak};
+///
+/// # fn main() -> Result<(), CustomError> {
+/// let cache = CacheDB::<String, String>::new_persistent(1000, "cache.txt")?;
+/// let listener = TcpListener::bind("127.0.0.1:6379")?;
+///
+/// // Utilisable avec `redis-cli -p 6379 SET pomme rouge`.
+/// resp::serve(listener, Arc::new(Mutex::new(cache)))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn serve(listener: TcpListener, cache: SharedCache) -> Result<(), CustomError> {
+    let broker = Arc::new(Broker::default());
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let cache = Arc::clone(&cache);
+        let broker = Arc::clone(&broker);
+        thread::spawn(move || {
+            let _ = handle_connection(stream, cache, broker);
+        });
+    }
+    Ok(())
+}
+
+/// Lance un thread qui supprime périodiquement les éléments expirés du cache partagé, ainsi que
+/// les éléments supprimés dont le délai de grâce est écoulé (voir `CacheDB::purge_tombstones`).
+///
+/// Sans ce thread, un élément expiré n'est supprimé que lors de sa prochaine lecture. Le thread
+/// s'arrête lorsque le cache n'est plus référencé ailleurs.
+///
+/// # Arguments
+///
+/// * `cache` - Le cache partagé à nettoyer.
+/// * `interval` - Le délai entre deux nettoyages.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use eval_rust::CacheDB;
+/// use eval_rust::errors::CustomError;
+/// use eval_rust::resp;
+/// use std::net::TcpListener;
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+///
+/// # fn main() -> Result<(), CustomError> {
+/// let cache = Arc::new(Mutex::new(CacheDB::<String, String>::new_persistent(1000, "cache.txt")?));
+/// resp::spawn_sweeper(&cache, Duration::from_secs(1));
+/// resp::serve(TcpListener::bind("127.0.0.1:6379")?, cache)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn spawn_sweeper(cache: &SharedCache, interval: Duration) -> JoinHandle<()> {
+    let cache: Weak<Mutex<CacheDB<String, String>>> = Arc::downgrade(cache);
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        let shared = match cache.upgrade() {
+            Some(shared) => shared,
+            None => break,
+        };
+        let mut cache = lock(&shared);
+        let _ = cache.purge_expired();
+        let _ = cache.purge_tombstones();
+    })
+}
+
+/// Traite les commandes d'une connexion jusqu'à sa fermeture.
+fn handle_connection(stream: TcpStream, cache: SharedCache, broker: Arc<Broker>) -> Result<(), CustomError> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    loop {
+        let command = match read_command(&mut reader) {
+            Ok(Some(command)) => command,
+            Ok(None) => break,
+            Err(CustomError::BadRequest) => {
+                writer.write_all(Reply::Error("Protocol error".to_string()).encode().as_bytes())?;
+                writer.flush()?;
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        if command.is_empty() {
+            continue;
+        }
+
+        let quit = command[0].eq_ignore_ascii_case("QUIT");
+        let reply = match (command[0].to_ascii_uppercase().as_str(), &command[1..]) {
+            ("QUIT", _) => Reply::Status("OK".to_string()),
+            ("SUBSCRIBE", channels) if !channels.is_empty() => return stream_messages(channels, &broker, reader, writer),
+            ("PUBLISH", [channel, message]) => Reply::Integer(broker.publish(channel, message) as i64),
+            ("SUBSCRIBE" | "PUBLISH", _) => {
+                Reply::Error(format!("wrong number of arguments for '{}' command", command[0]))
+            }
+            _ => execute(&command, &cache),
+        };
+
+        writer.write_all(reply.encode().as_bytes())?;
+        writer.flush()?;
+
+        if quit {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Abonne une connexion à des canaux, puis lui transmet les messages publiés jusqu'à sa fermeture.
+fn stream_messages(
+    channels: &[String],
+    broker: &Arc<Broker>,
+    mut reader: BufReader<TcpStream>,
+    mut writer: TcpStream,
+) -> Result<(), CustomError> {
+    let subscriber = broker.next_subscriber();
+    let (sender, receiver) = mpsc::channel();
+    for channel in channels {
+        broker.subscribe(channel, subscriber, sender.clone());
+    }
+    drop(sender);
+
+    // La connexion continue d'être lue dans un autre thread : lorsque le client la ferme, l'abonné
+    // est retiré de tous les canaux, ce qui ferme le canal de réception et termine l'envoi.
+    let watcher_broker = Arc::clone(broker);
+    thread::spawn(move || {
+        while let Ok(Some(_)) = read_command(&mut reader) {}
+        watcher_broker.unsubscribe(subscriber);
+    });
+
+    let result = send_messages(channels, receiver, &mut writer);
+    broker.unsubscribe(subscriber);
+    let _ = writer.shutdown(Shutdown::Both);
+    result
+}
+
+/// Confirme l'abonnement à chaque canal, puis envoie les messages reçus jusqu'à la fermeture du canal de réception.
+fn send_messages(channels: &[String], receiver: Receiver<Message>, writer: &mut TcpStream) -> Result<(), CustomError> {
+    for (count, channel) in channels.iter().enumerate() {
+        let confirmation = Reply::Array(vec![
+            Reply::Bulk(Some("subscribe".to_string())),
+            Reply::Bulk(Some(channel.clone())),
+            Reply::Integer(count as i64 + 1),
+        ]);
+        writer.write_all(confirmation.encode().as_bytes())?;
+    }
+    writer.flush()?;
+
+    for (channel, message) in receiver {
+        let message = Reply::Array(vec![
+            Reply::Bulk(Some("message".to_string())),
+            Reply::Bulk(Some(channel)),
+            Reply::Bulk(Some(message)),
+        ]);
+        writer.write_all(message.encode().as_bytes())?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Message publié sur un canal, sous la forme `(canal, message)`.
+type Message = (String, String);
+
+/// Abonné d'un canal, sous la forme `(numéro de l'abonné, émetteur vers sa connexion)`.
+type Subscriber = (u64, Sender<Message>);
+
+/// Canaux de publication partagés entre les connexions du serveur.
+///
+/// Chaque abonné est identifié par un numéro propre à sa connexion.
+#[derive(Default)]
+struct Broker {
+    channels: Mutex<HashMap<String, Vec<Subscriber>>>,
+    subscribers: AtomicU64,
+}
+
+impl Broker {
+    fn next_subscriber(&self) -> u64 {
+        self.subscribers.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn subscribe(&self, channel: &str, subscriber: u64, sender: Sender<Message>) {
+        lock(&self.channels).entry(channel.to_string()).or_default().push((subscriber, sender));
+    }
+
+    /// Retire un abonné de tous les canaux.
+    fn unsubscribe(&self, subscriber: u64) {
+        lock(&self.channels).retain(|_, subscribers| {
+            subscribers.retain(|(id, _)| *id != subscriber);
+            !subscribers.is_empty()
+        });
+    }
+
+    /// Transmet un message aux abonnés d'un canal et retourne le nombre d'abonnés qui l'ont reçu.
+    ///
+    /// Les abonnés dont la connexion est fermée sont retirés du canal.
+    fn publish(&self, channel: &str, message: &str) -> usize {
+        let mut channels = lock(&self.channels);
+        let subscribers = match channels.get_mut(channel) {
+            Some(subscribers) => subscribers,
+            None => return 0,
+        };
+        subscribers.retain(|(_, sender)| sender.send((channel.to_string(), message.to_string())).is_ok());
+        let count = subscribers.len();
+        if count == 0 {
+            channels.remove(channel);
+        }
+        count
+    }
+}
+
+/// Verrouille un mutex, y compris si un thread a paniqué en le détenant.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Réponse RESP à une commande.
+enum Reply {
+    Status(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<String>),
+    Array(Vec<Reply>),
+}
+
+impl Reply {
+    fn encode(&self) -> String {
+        match self {
+            Reply::Status(status) => format!("+{}\r\n", status),
+            Reply::Error(message) => format!("-ERR {}\r\n", message),
+            Reply::Integer(value) => format!(":{}\r\n", value),
+            Reply::Bulk(Some(value)) => format!("${}\r\n{}\r\n", value.len(), value),
+            Reply::Bulk(None) => "$-1\r\n".to_string(),
+            Reply::Array(values) => {
+                let mut encoded = format!("*{}\r\n", values.len());
+                for value in values {
+                    encoded.push_str(&value.encode());
+                }
+                encoded
+            }
+        }
+    }
+}
+
+/// Exécute une commande sur le cache partagé.
+fn execute(command: &[String], cache: &SharedCache) -> Reply {
+    let mut cache = lock(cache);
+
+    let name = command[0].to_ascii_uppercase();
+    let args = &command[1..];
+
+    match (name.as_str(), args) {
+        ("PING", []) => Reply::Status("PONG".to_string()),
+        ("PING", [message]) => Reply::Bulk(Some(message.clone())),
+        ("GET", [key]) => Reply::Bulk(cache.get(key).cloned()),
+        ("MGET", keys) if !keys.is_empty() => Reply::Array(
+            cache
+                .get_many(keys)
+                .into_iter()
+                .map(|item| match item.status {
+                    BatchStatus::Found(value) => Reply::Bulk(Some(value)),
+                    BatchStatus::Missing => Reply::Bulk(None),
+                })
+                .collect(),
+        ),
+        ("SET", [key, value]) => match cache.put(key.clone(), value.clone()) {
+            Ok(_) => Reply::Status("OK".to_string()),
+            Err(e) => Reply::Error(e.to_string()),
+        },
+        ("SETNX", [key, value]) => match cache.put_if_absent(key.clone(), value.clone()) {
+            Ok(_) => Reply::Integer(1),
+            Err(CustomError::PreconditionFailed) => Reply::Integer(0),
+            Err(e) => Reply::Error(e.to_string()),
+        },
+        ("DEL", keys) if !keys.is_empty() => {
+            let mut removed = 0;
+            for key in keys {
+                match cache.remove(key) {
+                    Ok(_) => removed += 1,
+                    Err(CustomError::NotFound) => {}
+                    Err(e) => return Reply::Error(e.to_string()),
+                }
+            }
+            Reply::Integer(removed)
+        }
+        ("EXPIRE", [key, seconds]) => {
+            let seconds = match seconds.parse() {
+                Ok(seconds) => seconds,
+                Err(_) => return Reply::Error("value is not an integer or out of range".to_string()),
+            };
+            match cache.expire(key, None, Some(Duration::from_secs(seconds))) {
+                Ok(()) => Reply::Integer(1),
+                Err(CustomError::NotFound) => Reply::Integer(0),
+                Err(e) => Reply::Error(e.to_string()),
+            }
+        }
+        ("TOUCH", keys) if !keys.is_empty() => {
+            let mut touched = 0;
+            for key in keys {
+                match cache.touch(key) {
+                    Ok(_) => touched += 1,
+                    Err(CustomError::NotFound) => {}
+                    Err(e) => return Reply::Error(e.to_string()),
+                }
+            }
+            Reply::Integer(touched)
+        }
+        ("TTL", [key]) => match cache.ttl(key) {
+            Some(ttl) => match ttl.remaining {
+                Some(remaining) => Reply::Integer(((remaining.as_millis() + 500) / 1000) as i64),
+                None => Reply::Integer(-1),
+            },
+            None => Reply::Integer(-2),
+        },
+        ("KEYS", [pattern]) => Reply::Array(
+            cache
+                .iter()
+                .filter(|(key, _)| glob_match(pattern, key))
+                .map(|(key, _)| Reply::Bulk(Some(key.clone())))
+                .collect(),
+        ),
+        ("PING" | "GET" | "MGET" | "SET" | "SETNX" | "DEL" | "EXPIRE" | "TOUCH" | "TTL" | "KEYS", _) => {
+            Reply::Error(format!("wrong number of arguments for '{}' command", command[0]))
+        }
+        _ => Reply::Error(format!("unknown command '{}'", command[0])),
+    }
+}
+
+/// Lit une commande, sous forme de tableau RESP ou de commande en ligne.
+///
+/// Retourne `None` lorsque la connexion est fermée, et `CustomError::BadRequest` si la commande
+/// est mal formée, si une ligne dépasse `MAX_INLINE_LENGTH` octets, ou si elle annonce plus de
+/// `MAX_MULTIBULK_LENGTH` arguments ou `MAX_BULK_LENGTH` octets par argument. Les limites sont
+/// vérifiées avant toute allocation.
+fn read_command<R: BufRead>(reader: &mut R) -> Result<Option<Vec<String>>, CustomError> {
+    let line = match read_line(reader)? {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+
+    let count = match line.strip_prefix('*') {
+        Some(count) => parse_length(count, MAX_MULTIBULK_LENGTH)?,
+        None => return Ok(Some(line.split_whitespace().map(str::to_string).collect())),
+    };
+
+    let mut command = Vec::with_capacity(count);
+    for _ in 0..count {
+        let header = read_line(reader)?.ok_or(CustomError::BadRequest)?;
+        let length = match header.strip_prefix('$') {
+            Some(length) => parse_length(length, MAX_BULK_LENGTH)?,
+            None => return Err(CustomError::BadRequest),
+        };
+
+        // Les données sont lues au fur et à mesure, sans réserver à l'avance la taille annoncée.
+        let expected = length.checked_add(2).ok_or(CustomError::BadRequest)?;
+        let mut data = Vec::new();
+        reader.by_ref().take(expected as u64).read_to_end(&mut data)?;
+        if data.len() != expected {
+            return Err(CustomError::BadRequest);
+        }
+        data.truncate(length);
+        command.push(String::from_utf8(data).map_err(|_| CustomError::BadRequest)?);
+    }
+
+    Ok(Some(command))
+}
+
+/// Lit une ligne terminée par `\r\n`, sans le terminateur.
+///
+/// Retourne `CustomError::BadRequest` si aucun terminateur n'est trouvé dans les
+/// `MAX_INLINE_LENGTH` premiers octets ou avant la fermeture de la connexion.
+fn read_line<R: BufRead>(reader: &mut R) -> Result<Option<String>, CustomError> {
+    let mut line = Vec::new();
+    if reader.by_ref().take(MAX_INLINE_LENGTH as u64).read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+    if line.last() != Some(&b'\n') {
+        return Err(CustomError::BadRequest);
+    }
+    let line = String::from_utf8(line).map_err(|_| CustomError::BadRequest)?;
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+/// Lit une longueur d'en-tête RESP, rejetée si elle dépasse `max`.
+fn parse_length(length: &str, max: usize) -> Result<usize, CustomError> {
+    match length.parse() {
+        Ok(length) if length <= max => Ok(length),
+        _ => Err(CustomError::BadRequest),
+    }
+}
+
+/// Indique si un texte correspond à un motif de style glob (`*` et `?`), comme `KEYS` de Redis.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,186 @@
+use crate::errors::CustomError;
+use crate::events::CacheEventListener;
+use crate::persistent::CacheDB;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Observateur qui reproduit les modifications d'un cache dans un cache secondaire, afin de
+/// valider un nouveau stockage sur un trafic réel avant de l'utiliser.
+///
+/// Les insertions sont reproduites avec `put`, et toutes les suppressions (explicites, évictions,
+/// expirations, valeurs corrompues) avec `remove`. Les écritures sont faites pendant la
+/// notification, sous le verrou du cache secondaire : l'opération du cache principal ne se
+/// termine qu'une fois la copie écrite, et attend si le cache secondaire est verrouillé ailleurs.
+/// Une erreur du cache secondaire n'interrompt pas le cache principal et est seulement comptée.
+/// `divergence` compare ensuite les deux caches.
+///
+/// # Exemples
+///
+/// ```
+/// use eval_rust::{CacheDB, ShadowWriter};
+/// use eval_rust::errors::CustomError;
+/// use std::sync::{Arc, Mutex};
+///
+/// # fn main() -> Result<(), CustomError> {
+/// let secondary = CacheDB::<String, i32>::new_persistent(5, "cache_shadow_secondary.txt")?;
+/// let shadow = ShadowWriter::new(Arc::new(Mutex::new(secondary)));
+///
+/// let mut cache = CacheDB::<String, i32>::new_persistent(5, "cache_shadow.txt")?;
+/// cache.subscribe(shadow.clone());
+/// cache.put("pomme".to_string(), 1)?;
+///
+/// assert!(shadow.divergence(&cache).is_consistent());
+/// # std::fs::remove_file("cache_shadow.txt")?;
+/// # std::fs::remove_file("cache_shadow_secondary.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ShadowWriter<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    shadow: Arc<Mutex<CacheDB<K, V>>>,
+    failed_writes: Arc<AtomicU64>,
+}
+
+/// Différences entre un cache et son cache secondaire, retournées par `ShadowWriter::divergence`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivergenceReport<K> {
+    /// Les clés présentes dans le cache principal mais absentes du cache secondaire.
+    pub missing: Vec<K>,
+    /// Les clés présentes dans le cache secondaire mais absentes du cache principal.
+    pub unexpected: Vec<K>,
+    /// Les clés dont les valeurs diffèrent entre les deux caches.
+    pub mismatched: Vec<K>,
+    /// Le nombre d'écritures qui ont échoué dans le cache secondaire.
+    pub failed_writes: u64,
+}
+
+impl<K> DivergenceReport<K> {
+    /// Indique si les deux caches ont le même contenu et si aucune écriture n'a échoué.
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty() && self.mismatched.is_empty() && self.failed_writes == 0
+    }
+}
+
+impl<K, V> ShadowWriter<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Crée un observateur qui reproduit les modifications dans le cache secondaire spécifié.
+    ///
+    /// # Arguments
+    ///
+    /// * `shadow` - Le cache secondaire, partagé avec l'appelant.
+    pub fn new(shadow: Arc<Mutex<CacheDB<K, V>>>) -> Self {
+        ShadowWriter {
+            shadow,
+            failed_writes: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Compare le contenu d'un cache à celui du cache secondaire.
+    ///
+    /// Les valeurs sont comparées sous leur forme JSON, sans modifier l'ordre d'utilisation des
+    /// éléments des deux caches.
+    ///
+    /// # Arguments
+    ///
+    /// * `primary` - Le cache dont les modifications sont reproduites.
+    pub fn divergence(&self, primary: &CacheDB<K, V>) -> DivergenceReport<K> {
+        let mut report = DivergenceReport {
+            missing: Vec::new(),
+            unexpected: Vec::new(),
+            mismatched: Vec::new(),
+            failed_writes: self.failed_writes.load(Ordering::SeqCst),
+        };
+        let shadow = match self.shadow.lock() {
+            Ok(shadow) => shadow,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        for (key, value) in primary.iter() {
+            match shadow.peek(key) {
+                Some(shadow_value) if !same_json(value, shadow_value) => report.mismatched.push(key.clone()),
+                Some(_) => {}
+                None => report.missing.push(key.clone()),
+            }
+        }
+        for (key, _) in shadow.iter() {
+            if !primary.contains_key(key) {
+                report.unexpected.push(key.clone());
+            }
+        }
+        report
+    }
+
+    /// Applique une modification au cache secondaire, en comptant les échecs.
+    fn mirror<F>(&self, write: F)
+    where
+        F: FnOnce(&mut CacheDB<K, V>) -> bool,
+    {
+        let succeeded = match self.shadow.lock() {
+            Ok(mut shadow) => write(&mut shadow),
+            Err(_) => false,
+        };
+        if !succeeded {
+            self.failed_writes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn mirror_remove(&self, key: &K) {
+        // Une clé déjà absente du cache secondaire n'est pas une erreur d'écriture.
+        self.mirror(|shadow| matches!(shadow.remove(key), Ok(_) | Err(CustomError::NotFound)));
+    }
+}
+
+impl<K, V> Clone for ShadowWriter<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    fn clone(&self) -> Self {
+        ShadowWriter {
+            shadow: self.shadow.clone(),
+            failed_writes: self.failed_writes.clone(),
+        }
+    }
+}
+
+impl<K, V> CacheEventListener<K, V> for ShadowWriter<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    fn on_insert(&self, key: &K, value: &V) {
+        self.mirror(|shadow| shadow.put(key.clone(), value.clone()).is_ok());
+    }
+
+    fn on_remove(&self, key: &K, _value: &V) {
+        self.mirror_remove(key);
+    }
+
+    fn on_evict(&self, key: &K, _value: &V) {
+        self.mirror_remove(key);
+    }
+
+    fn on_expire(&self, key: &K, _value: &V) {
+        self.mirror_remove(key);
+    }
+
+    fn on_corrupted(&self, key: &K, _value: &V) {
+        self.mirror_remove(key);
+    }
+}
+
+/// Indique si deux valeurs ont la même forme JSON.
+fn same_json<V: Serialize>(a: &V, b: &V) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
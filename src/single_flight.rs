@@ -0,0 +1,120 @@
+use crate::errors::CustomError;
+use crate::persistent::CacheDB;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// Coordination des chargements d'un cache partagé entre plusieurs threads.
+///
+/// Lorsque plusieurs threads manquent la même clé en même temps, un seul appelle la fonction de
+/// chargement ; les autres attendent son résultat au lieu de relancer un chargement coûteux.
+/// Le cache n'est pas verrouillé pendant le chargement, si bien que les autres clés restent accessibles.
+///
+/// # Exemples
+///
+/// ```
+/// use eval_rust::{CacheDB, SingleFlight};
+/// use eval_rust::errors::CustomError;
+/// use std::sync::{Arc, Mutex};
+/// use std::thread;
+///
+/// # fn main() -> Result<(), CustomError> {
+/// let cache = Arc::new(Mutex::new(CacheDB::<String, String>::new_persistent(5, "cache_single_flight.txt")?));
+/// let single_flight = Arc::new(SingleFlight::new());
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|_| {
+///         let (cache, single_flight) = (cache.clone(), single_flight.clone());
+///         thread::spawn(move || {
+///             single_flight.get_or_load(&cache, "profil".to_string(), || Ok::<_, CustomError>("chargé".to_string()))
+///         })
+///     })
+///     .collect();
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap()?, "chargé");
+/// }
+/// # std::fs::remove_file("cache_single_flight.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SingleFlight<K> {
+    in_flight: Mutex<HashSet<K>>,
+    done: Condvar,
+}
+
+impl<K: Eq + Hash + Clone> SingleFlight<K> {
+    /// Crée une coordination sans chargement en cours.
+    pub fn new() -> Self {
+        SingleFlight {
+            in_flight: Mutex::new(HashSet::new()),
+            done: Condvar::new(),
+        }
+    }
+
+    /// Récupère la valeur associée à une clé dans un cache partagé, en la chargeant si elle est absente.
+    ///
+    /// Si un autre thread charge déjà la clé, l'appel attend la fin de ce chargement puis relit le
+    /// cache. Si ce chargement a échoué ou paniqué, l'appel tente à son tour de charger la clé.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - Le cache partagé.
+    /// * `key` - La clé à rechercher.
+    /// * `loader` - La fonction qui charge la valeur d'une clé absente.
+    ///
+    /// # Retour
+    ///
+    /// Retourne une copie de la valeur, l'erreur de la fonction de chargement, ou une erreur
+    /// `CustomError` convertie si le cache n'a pas pu être lu ou modifié.
+    pub fn get_or_load<V, F, E>(&self, cache: &Mutex<CacheDB<K, V>>, key: K, loader: F) -> Result<V, E>
+    where
+        K: ToString + Serialize + for<'de> Deserialize<'de>,
+        V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+        F: FnOnce() -> Result<V, E>,
+        E: From<CustomError>,
+    {
+        let mut in_flight = lock(&self.in_flight);
+        loop {
+            if let Some(value) = lock(cache).try_get(&key)?.cloned() {
+                return Ok(value);
+            }
+            if in_flight.insert(key.clone()) {
+                break;
+            }
+            in_flight = self.done.wait(in_flight).unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        drop(in_flight);
+
+        let _guard = InFlightGuard { single_flight: self, key: &key };
+        loader().and_then(|value| {
+            lock(cache).put(key.clone(), value.clone())?;
+            Ok(value)
+        })
+    }
+}
+
+/// Termine le chargement d'une clé à sa destruction, y compris si la fonction de chargement panique :
+/// la clé est retirée des chargements en cours et les threads en attente sont réveillés.
+struct InFlightGuard<'a, K: Eq + Hash> {
+    single_flight: &'a SingleFlight<K>,
+    key: &'a K,
+}
+
+impl<K: Eq + Hash> Drop for InFlightGuard<'_, K> {
+    fn drop(&mut self) {
+        lock(&self.single_flight.in_flight).remove(self.key);
+        self.single_flight.done.notify_all();
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for SingleFlight<K> {
+    fn default() -> Self {
+        SingleFlight::new()
+    }
+}
+
+/// Verrouille un mutex, y compris si un thread a paniqué en le détenant.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+/// Opération dont la durée a dépassé le seuil du journal des opérations lentes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowOperation {
+    /// Le nom de l'opération : `put`, `put_with_ttl`, `remove`, `clear` ou `save`.
+    pub name: &'static str,
+    /// La durée de l'opération, sauvegarde du fichier comprise.
+    pub duration: Duration,
+    /// La date de fin de l'opération.
+    pub recorded_at: SystemTime,
+}
+
+/// Journal en mémoire des dernières opérations lentes d'un cache.
+#[derive(Debug)]
+pub(crate) struct SlowLog {
+    threshold: Duration,
+    max_entries: usize,
+    entries: VecDeque<SlowOperation>,
+}
+
+impl SlowLog {
+    pub(crate) fn new(threshold: Duration, max_entries: usize) -> Self {
+        SlowLog {
+            threshold,
+            max_entries,
+            entries: VecDeque::with_capacity(max_entries),
+        }
+    }
+
+    /// Enregistre une opération si sa durée atteint le seuil, en oubliant la plus ancienne si le journal est plein.
+    pub(crate) fn record(&mut self, name: &'static str, duration: Duration) {
+        if duration < self.threshold || self.max_entries == 0 {
+            return;
+        }
+        if self.entries.len() == self.max_entries {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(SlowOperation {
+            name,
+            duration,
+            recorded_at: SystemTime::now(),
+        });
+    }
+
+    pub(crate) fn entries(&self) -> Vec<SlowOperation> {
+        self.entries.iter().cloned().collect()
+    }
+}
@@ -0,0 +1,92 @@
+use serde::Serialize;
+use std::time::{Duration, SystemTime};
+
+/// Statistiques d'utilisation d'un cache, retournées par `CacheDB::stats`.
+///
+/// Les compteurs de lectures et d'évictions et la durée d'ouverture portent sur l'instance du
+/// cache : ils ne sont pas persistés et repartent de zéro à chaque ouverture du fichier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// Le nombre d'éléments dans le cache.
+    pub len: usize,
+    /// Le nombre maximal d'éléments du cache.
+    pub capacity: usize,
+    /// La mémoire occupée par les éléments, en octets.
+    pub used_bytes: usize,
+    /// Le budget mémoire du cache, s'il est limité.
+    pub max_bytes: Option<usize>,
+    /// Le nombre de sauvegardes du fichier du cache.
+    pub persist_count: u64,
+    /// La durée de la dernière sauvegarde, si le cache a déjà été sauvegardé.
+    pub last_persist_duration: Option<Duration>,
+    /// La durée cumulée de toutes les sauvegardes.
+    pub total_persist_duration: Duration,
+    /// La taille du fichier du cache après la dernière sauvegarde, en octets.
+    pub file_size: Option<u64>,
+    /// La date de la dernière sauvegarde, si le cache a déjà été sauvegardé.
+    pub last_persist_at: Option<SystemTime>,
+    /// Le nombre de lectures de clés présentes dans le cache.
+    pub hits: u64,
+    /// Le nombre de lectures de clés absentes du cache.
+    pub misses: u64,
+    /// Le nombre d'éléments évincés.
+    pub evictions: u64,
+    /// La durée écoulée depuis l'ouverture du cache.
+    pub uptime: Duration,
+    /// Le nombre d'observateurs enregistrés avec `subscribe` ou `watch`.
+    pub listeners: usize,
+}
+
+impl CacheStats {
+    /// Retourne la proportion de lectures de clés présentes dans le cache, ou `None` si aucune lecture n'a eu lieu.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let reads = self.hits + self.misses;
+        (reads > 0).then(|| self.hits as f64 / reads as f64)
+    }
+}
+
+/// Mesures des sauvegardes d'un cache, reprises dans `CacheStats`.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PersistMetrics {
+    pub(crate) count: u64,
+    pub(crate) last_duration: Option<Duration>,
+    pub(crate) total_duration: Duration,
+    pub(crate) file_size: Option<u64>,
+    pub(crate) last_at: Option<SystemTime>,
+}
+
+impl PersistMetrics {
+    /// Enregistre une sauvegarde et la taille du fichier obtenu.
+    pub(crate) fn record(&mut self, duration: Duration, file_size: u64) {
+        self.count += 1;
+        self.last_duration = Some(duration);
+        self.total_duration += duration;
+        self.file_size = Some(file_size);
+        self.last_at = Some(SystemTime::now());
+    }
+}
+
+/// Compteurs de lectures et d'évictions d'un cache, repris dans `CacheStats`.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct AccessCounters {
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) evictions: u64,
+}
+
+/// Statistiques d'utilisation d'un cache à deux niveaux, retournées par `TieredCache::stats`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TieredStats {
+    /// Le nombre d'éléments du niveau en mémoire.
+    pub hot_len: usize,
+    /// Le nombre maximal d'éléments du niveau en mémoire.
+    pub hot_capacity: usize,
+    /// Les statistiques du niveau persistant.
+    pub cold: CacheStats,
+    /// Le nombre de lectures servies par le niveau en mémoire.
+    pub hot_hits: u64,
+    /// Le nombre de lectures servies par le niveau persistant.
+    pub cold_hits: u64,
+    /// Le nombre de lectures de clés absentes des deux niveaux.
+    pub misses: u64,
+}
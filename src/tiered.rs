@@ -0,0 +1,223 @@
+use crate::errors::CustomError;
+use crate::persistent::CacheDB;
+use crate::stats::TieredStats;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// Cache à deux niveaux : un petit niveau en mémoire devant un `CacheDB` persistant.
+///
+/// Un élément se trouve dans un seul niveau à la fois. Les insertions et les lectures placent
+/// l'élément dans le niveau en mémoire ; lorsqu'il est plein, son élément le moins récemment
+/// utilisé est rétrogradé dans le niveau persistant. Un élément lu dans le niveau persistant
+/// est promu dans le niveau en mémoire.
+///
+/// Les éléments du niveau en mémoire ne sont écrits dans le fichier que lorsqu'ils sont
+/// rétrogradés, lors d'un appel à `flush` ou à la destruction du cache. Une promotion retire
+/// l'élément du niveau persistant sans notifier ses observateurs ni réécrire son fichier, qui
+/// est mis à jour lors de la sauvegarde suivante.
+///
+/// # Exemples
+///
+/// ```
+/// use eval_rust::TieredCache;
+/// use eval_rust::errors::CustomError;
+///
+/// # fn main() -> Result<(), CustomError> {
+/// let mut cache = TieredCache::<String, i32>::new_persistent(1, 100, "cache_tiered.txt")?;
+/// cache.put("pomme".to_string(), 1)?;
+/// cache.put("banane".to_string(), 2)?; // "pomme" est rétrogradé
+///
+/// assert_eq!(cache.get(&"pomme".to_string())?, Some(&1)); // "pomme" est promu
+/// assert_eq!(cache.stats().cold_hits, 1);
+/// # drop(cache);
+/// # std::fs::remove_file("cache_tiered.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TieredCache<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    hot: Vec<(K, V)>,
+    hot_capacity: usize,
+    cold: CacheDB<K, V>,
+    hot_hits: u64,
+    cold_hits: u64,
+    misses: u64,
+}
+
+impl<K, V> TieredCache<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Crée un cache à deux niveaux dont le niveau persistant est stocké dans le fichier spécifié.
+    ///
+    /// # Arguments
+    ///
+    /// * `hot_capacity` - La capacité du niveau en mémoire.
+    /// * `cold_capacity` - La capacité du niveau persistant.
+    /// * `file_path` - Le chemin du fichier du niveau persistant.
+    ///
+    /// # Retour
+    ///
+    /// Retourne un `Result` ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn new_persistent(hot_capacity: usize, cold_capacity: usize, file_path: &str) -> Result<Self, CustomError> {
+        let cold = CacheDB::new_persistent(cold_capacity, file_path)?;
+        Ok(TieredCache::from_cache(hot_capacity, cold))
+    }
+
+    /// Crée un cache à deux niveaux à partir d'un `CacheDB` existant, utilisé comme niveau persistant.
+    ///
+    /// # Arguments
+    ///
+    /// * `hot_capacity` - La capacité du niveau en mémoire.
+    /// * `cold` - Le niveau persistant.
+    pub fn from_cache(hot_capacity: usize, cold: CacheDB<K, V>) -> Self {
+        TieredCache {
+            hot: Vec::with_capacity(hot_capacity),
+            hot_capacity,
+            cold,
+            hot_hits: 0,
+            cold_hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Insère une paire clé-valeur dans le niveau en mémoire.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à insérer.
+    /// * `value` - La valeur à associer à la clé.
+    ///
+    /// # Retour
+    ///
+    /// Retourne la valeur précédemment associée à la clé dans l'un des niveaux, ou `None`,
+    /// ou une erreur `CustomError` si le niveau persistant n'a pas pu être modifié.
+    pub fn put(&mut self, key: K, value: V) -> Result<Option<V>, CustomError> {
+        let previous = match self.hot_position(&key) {
+            Some(index) => Some(self.hot.remove(index).1),
+            None => self.remove_cold(&key)?,
+        };
+        self.hot.push((key, value));
+        self.demote_overflow()?;
+        Ok(previous)
+    }
+
+    /// Récupère la valeur associée à une clé, en la promouvant dans le niveau en mémoire.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(Some(&V))` si la clé est trouvée, `Ok(None)` sinon, ou une erreur `CustomError`
+    /// si la promotion a échoué.
+    pub fn get(&mut self, key: &K) -> Result<Option<&V>, CustomError> {
+        if let Some(index) = self.hot_position(key) {
+            self.hot_hits += 1;
+            let entry = self.hot.remove(index);
+            self.hot.push(entry);
+        } else {
+            match self.remove_cold(key)? {
+                Some(value) => {
+                    self.cold_hits += 1;
+                    self.hot.push((key.clone(), value));
+                    self.demote_overflow()?;
+                }
+                None => {
+                    self.misses += 1;
+                    return Ok(None);
+                }
+            }
+        }
+        // Avec un niveau en mémoire de capacité nulle, l'élément promu est aussitôt rétrogradé.
+        match self.hot_position(key) {
+            Some(index) => Ok(Some(&self.hot[index].1)),
+            None => Ok(self.cold.get(key)),
+        }
+    }
+
+    /// Supprime l'élément associé à une clé, dans l'un ou l'autre des niveaux.
+    ///
+    /// # Retour
+    ///
+    /// Retourne la valeur supprimée, `CustomError::NotFound` si la clé n'est dans aucun niveau,
+    /// ou une autre erreur `CustomError` si une erreur s'est produite.
+    pub fn remove(&mut self, key: &K) -> Result<V, CustomError> {
+        match self.hot_position(key) {
+            Some(index) => Ok(self.hot.remove(index).1),
+            None => self.cold.remove(key),
+        }
+    }
+
+    /// Rétrograde tous les éléments du niveau en mémoire et sauvegarde le niveau persistant.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si le cache a été sauvegardé avec succès, ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn flush(&mut self) -> Result<(), CustomError> {
+        for (key, value) in self.hot.drain(..) {
+            self.cold.put(key, value)?;
+        }
+        self.cold.flush()
+    }
+
+    /// Retourne le nombre d'éléments dans les deux niveaux.
+    pub fn len(&self) -> usize {
+        self.hot.len() + self.cold.len()
+    }
+
+    /// Indique si les deux niveaux sont vides.
+    pub fn is_empty(&self) -> bool {
+        self.hot.is_empty() && self.cold.is_empty()
+    }
+
+    /// Retourne les statistiques d'utilisation des deux niveaux.
+    pub fn stats(&self) -> TieredStats {
+        TieredStats {
+            hot_len: self.hot.len(),
+            hot_capacity: self.hot_capacity,
+            cold: self.cold.stats(),
+            hot_hits: self.hot_hits,
+            cold_hits: self.cold_hits,
+            misses: self.misses,
+        }
+    }
+
+    /// Retourne une référence vers le niveau persistant.
+    pub fn cold(&self) -> &CacheDB<K, V> {
+        &self.cold
+    }
+
+    fn hot_position(&self, key: &K) -> Option<usize> {
+        self.hot.iter().position(|(hot_key, _)| hot_key == key)
+    }
+
+    /// Retire une clé du niveau persistant et retourne sa valeur, ou `None` si elle n'y est pas.
+    fn remove_cold(&mut self, key: &K) -> Result<Option<V>, CustomError> {
+        self.cold.detach(key)
+    }
+
+    /// Rétrograde les éléments les moins récemment utilisés tant que le niveau en mémoire est plein.
+    fn demote_overflow(&mut self) -> Result<(), CustomError> {
+        while self.hot.len() > self.hot_capacity {
+            let (key, value) = self.hot.remove(0);
+            self.cold.put(key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K, V> Drop for TieredCache<K, V>
+where
+    K: Eq + Hash + Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+    V: Clone + ToString + Serialize + for<'de> Deserialize<'de>,
+{
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
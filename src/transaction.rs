@@ -0,0 +1,43 @@
+/// Modifications d'un cache regroupées par `CacheDB::transaction`, appliquées ensemble ou pas du tout.
+///
+/// Les méthodes ne font qu'enregistrer les modifications : elles sont appliquées dans leur ordre
+/// d'enregistrement à la fin de la transaction.
+pub struct Transaction<K, V> {
+    pub(crate) operations: Vec<TransactionOperation<K, V>>,
+}
+
+/// Modification enregistrée dans une transaction.
+pub(crate) enum TransactionOperation<K, V> {
+    Put { key: K, value: V },
+    Remove { key: K },
+}
+
+impl<K, V> Transaction<K, V> {
+    pub(crate) fn new() -> Self {
+        Transaction { operations: Vec::new() }
+    }
+
+    /// Insère une paire clé-valeur comme avec `CacheDB::put`.
+    pub fn put(&mut self, key: K, value: V) -> &mut Self {
+        self.operations.push(TransactionOperation::Put { key, value });
+        self
+    }
+
+    /// Supprime l'élément associé à une clé comme avec `CacheDB::remove`.
+    ///
+    /// La transaction échoue si la clé n'est pas dans le cache au moment de la suppression.
+    pub fn remove(&mut self, key: K) -> &mut Self {
+        self.operations.push(TransactionOperation::Remove { key });
+        self
+    }
+
+    /// Retourne le nombre de modifications enregistrées.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Indique si aucune modification n'a été enregistrée.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
@@ -0,0 +1,154 @@
+use crate::errors::CustomError;
+use crate::persistent::CacheDB;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// Valeur stockée par un `TypedCache` : la valeur sérialisée en JSON et la version de son schéma.
+#[derive(Serialize, Deserialize)]
+struct TypedValue {
+    version: u32,
+    data: serde_json::Value,
+}
+
+/// Cache typé construit au-dessus d'un `CacheDB<String, String>`.
+///
+/// Les valeurs sont sérialisées en JSON et étiquetées avec une version de schéma. Une valeur
+/// écrite avec une autre version est signalée par `CustomError::SchemaVersionMismatch` au lieu
+/// d'être désérialisée dans le mauvais type.
+///
+/// # Exemples
+///
+/// ```
+/// use eval_rust::TypedCache;
+/// use eval_rust::errors::CustomError;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Fruit {
+///     couleur: String,
+///     prix: u32,
+/// }
+///
+/// # fn main() -> Result<(), CustomError> {
+/// let mut cache = TypedCache::<Fruit>::new_persistent(5, "cache_typed.txt", 1)?;
+///
+/// cache.put("pomme".to_string(), &Fruit { couleur: "rouge".to_string(), prix: 2 })?;
+///
+/// let pomme = cache.get("pomme")?;
+/// assert_eq!(pomme, Some(Fruit { couleur: "rouge".to_string(), prix: 2 }));
+/// # std::fs::remove_file("cache_typed.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TypedCache<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    inner: CacheDB<String, String>,
+    schema_version: u32,
+    marker: PhantomData<T>,
+}
+
+impl<T> TypedCache<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Crée un cache typé persistant, avec la capacité, le chemin du fichier et la version de schéma spécifiés.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - La capacité maximale du cache.
+    /// * `file_path` - Le chemin du fichier où le cache sera stocké.
+    /// * `schema_version` - La version du schéma des valeurs écrites par ce cache.
+    ///
+    /// # Retour
+    ///
+    /// Retourne un `Result` ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn new_persistent(capacity: usize, file_path: &str, schema_version: u32) -> Result<Self, CustomError> {
+        let inner = CacheDB::new_persistent(capacity, file_path)?;
+        Ok(TypedCache::from_cache(inner, schema_version))
+    }
+
+    /// Crée un cache typé à partir d'un `CacheDB<String, String>` existant.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - Le cache dans lequel les valeurs sérialisées sont stockées.
+    /// * `schema_version` - La version du schéma des valeurs écrites par ce cache.
+    pub fn from_cache(inner: CacheDB<String, String>, schema_version: u32) -> Self {
+        TypedCache {
+            inner,
+            schema_version,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sérialise une valeur et l'insère dans le cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à insérer.
+    /// * `value` - La valeur à associer à la clé.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si l'insertion a réussi, ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn put(&mut self, key: String, value: &T) -> Result<(), CustomError> {
+        let typed_value = TypedValue {
+            version: self.schema_version,
+            data: serde_json::to_value(value)?,
+        };
+        let json = serde_json::to_string(&typed_value)?;
+        self.inner.put(key, json)?;
+        Ok(())
+    }
+
+    /// Récupère et désérialise la valeur associée à une clé.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - La clé à rechercher.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(Some(T))` si la clé est trouvée, `Ok(None)` si elle n'est pas dans le cache,
+    /// `CustomError::SchemaVersionMismatch` si la valeur a été écrite avec une autre version de schéma,
+    /// ou `CustomError::SerializationError` si elle ne peut pas être désérialisée.
+    pub fn get(&mut self, key: &str) -> Result<Option<T>, CustomError> {
+        let json = match self.inner.get(&key.to_string()) {
+            Some(json) => json,
+            None => return Ok(None),
+        };
+
+        let typed_value: TypedValue = serde_json::from_str(json)?;
+        if typed_value.version != self.schema_version {
+            return Err(CustomError::SchemaVersionMismatch {
+                expected: self.schema_version,
+                found: typed_value.version,
+            });
+        }
+
+        Ok(Some(serde_json::from_value(typed_value.data)?))
+    }
+
+    /// Supprime l'élément associé à une clé du cache.
+    ///
+    /// # Retour
+    ///
+    /// Retourne `Ok(())` si la clé a été trouvée et supprimée, ou une erreur `CustomError` si une erreur s'est produite.
+    pub fn remove(&mut self, key: &str) -> Result<(), CustomError> {
+        self.inner.remove(&key.to_string())?;
+        Ok(())
+    }
+
+    /// Retourne une référence vers le cache sous-jacent.
+    pub fn inner(&self) -> &CacheDB<String, String> {
+        &self.inner
+    }
+
+    /// Consomme le cache typé et retourne le cache sous-jacent.
+    pub fn into_inner(self) -> CacheDB<String, String> {
+        self.inner
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+/// Délai minimal entre deux vérifications du thread de sauvegarde, pour qu'un intervalle nul ne
+/// le fasse pas tourner en boucle.
+const MIN_FLUSH_WAIT: Duration = Duration::from_millis(10);
+
+/// État de la persistance différée (write-behind) d'un cache.
+///
+/// Les modifications sont comptabilisées et le cache n'est écrit sur disque
+/// qu'après `max_pending` modifications ou lorsque `interval` s'est écoulé
+/// depuis la dernière écriture.
+#[derive(Debug)]
+pub(crate) struct WriteBehind {
+    interval: Duration,
+    max_pending: usize,
+    pending: usize,
+    last_flush: Instant,
+}
+
+impl WriteBehind {
+    pub(crate) fn new(interval: Duration, max_pending: usize) -> Self {
+        WriteBehind {
+            interval,
+            max_pending,
+            pending: 0,
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Enregistre une modification et indique si le cache doit être écrit sur disque.
+    pub(crate) fn record_mutation(&mut self) -> bool {
+        self.pending += 1;
+        self.pending >= self.max_pending || self.last_flush.elapsed() >= self.interval
+    }
+
+    /// Indique si des modifications n'ont pas encore été écrites sur disque.
+    pub(crate) fn is_dirty(&self) -> bool {
+        self.pending > 0
+    }
+
+    /// Indique si des modifications attendent depuis au moins `interval` sans avoir été écrites.
+    pub(crate) fn is_due(&self) -> bool {
+        self.is_dirty() && self.last_flush.elapsed() >= self.interval
+    }
+
+    /// Retourne le délai avant que les modifications en attente ne doivent être écrites, d'au
+    /// moins `MIN_FLUSH_WAIT`.
+    pub(crate) fn time_until_due(&self) -> Duration {
+        let wait = match self.interval.saturating_sub(self.last_flush.elapsed()) {
+            remaining if self.is_dirty() && !remaining.is_zero() => remaining,
+            _ => self.interval,
+        };
+        wait.max(MIN_FLUSH_WAIT)
+    }
+
+    /// Réinitialise l'état après une écriture sur disque.
+    pub(crate) fn mark_flushed(&mut self) {
+        self.pending = 0;
+        self.last_flush = Instant::now();
+    }
+}
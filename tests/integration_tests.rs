@@ -1,6 +1,30 @@
+use eval_rust::BatchStatus;
+use eval_rust::FnBackingStore;
+use eval_rust::LockMode;
 use eval_rust::CacheDB;
+use eval_rust::CacheEventListener;
+use eval_rust::CacheEvent;
+use eval_rust::Entry;
+use eval_rust::CapacityPolicy;
 use eval_rust::CustomError;
+use eval_rust::ExportFormat;
+use eval_rust::TieredCache;
+use eval_rust::TypedCache;
+use eval_rust::ShadowWriter;
+use eval_rust::SingleFlight;
+use eval_rust::resp;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 #[test]
 fn test_cache_put_get_remove() {
@@ -102,8 +126,31 @@ fn test_cache_clear() {
     assert_eq!(cache.len(), 0);
 
     // Vérifie que le cache est vide après le chargement
-    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 0);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_clear_count() {
+    let file_path = "test_cache_clear_count.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_soft_delete(Duration::from_secs(3600));
+    assert_eq!(cache.clear().unwrap(), 0);
+
+    // Un élément présent, un élément expiré pas encore purgé et un élément supprimé réversiblement
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put_with_ttl("kiwi".to_string(), "vert".to_string(), None, Some(Duration::from_millis(50))).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.remove(&"banane".to_string()).is_ok());
+    thread::sleep(Duration::from_millis(100));
+
+    assert_eq!(cache.clear().unwrap(), 3);
     assert_eq!(cache.len(), 0);
+    assert!(matches!(cache.restore(&"banane".to_string()), Err(CustomError::NotFound)));
+    assert_eq!(cache.clear().unwrap(), 0);
 
     fs::remove_file(file_path).unwrap();
 }
@@ -116,7 +163,7 @@ fn test_cache_empty_file() {
     fs::write(file_path, "").unwrap();
 
     // Vérifie que le chargement d'un fichier vide ne pose pas de problème
-    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
     assert_eq!(cache.len(), 0);
 
     fs::remove_file(file_path).unwrap();
@@ -144,9 +191,9 @@ fn test_cache_iter() {
 
     let mut count = 0;
     for (key, value) in cache.iter() {
-        if *key == String::from("1") {
+        if key == "1" {
             assert_eq!(*value, String::from("un"));
-        } else if *key == String::from("pomme") {
+        } else if key == "pomme" {
             assert_eq!(*value, String::from("rouge"));
         } else {
             panic!("Clé inattendue: {}", key);
@@ -174,4 +221,1911 @@ fn test_cache_len() {
     assert_eq!(cache.len(), 0);
 
     fs::remove_file(file_path).unwrap();
-}
\ No newline at end of file
+}
+#[test]
+fn test_cache_scan_prefix() {
+    let file_path = "test_cache_scan_prefix.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+
+    assert!(cache.put("user:1".to_string(), "alice".to_string()).is_ok());
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("user:2".to_string(), "bob".to_string()).is_ok());
+
+    let results = cache.scan_prefix("user:");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0], (0, &"user:1".to_string(), &"alice".to_string()));
+    assert_eq!(results[1], (2, &"user:2".to_string(), &"bob".to_string()));
+
+    // Aucun résultat pour un préfixe inconnu
+    assert!(cache.scan_prefix("banane").is_empty());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_write_behind() {
+    let file_path = "test_cache_write_behind.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_write_behind(Duration::from_secs(60), 3);
+
+    // Les modifications restent en mémoire tant que le seuil n'est pas atteint
+    assert!(cache.put("1".to_string(), "un".to_string()).is_ok());
+    assert!(cache.put("2".to_string(), "deux".to_string()).is_ok());
+    assert_eq!(cache.get(&"1".to_string()), Some(&"un".to_string()));
+    assert!(!Path::new(file_path).exists());
+
+    // La troisième modification déclenche la sauvegarde
+    assert!(cache.put("3".to_string(), "trois".to_string()).is_ok());
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.len(), 3);
+    drop(reloaded);
+
+    // flush force la sauvegarde
+    assert!(cache.remove(&"1".to_string()).is_ok());
+    assert!(cache.flush().is_ok());
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.len(), 2);
+    drop(reloaded);
+
+    // Les modifications en attente sont sauvegardées à la destruction du cache
+    assert!(cache.put("4".to_string(), "quatre".to_string()).is_ok());
+    drop(cache);
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.len(), 3);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_write_behind_flusher() {
+    let file_path = "test_cache_write_behind_flusher.txt";
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_write_behind(Duration::from_millis(50), 100);
+    let cache = Arc::new(Mutex::new(cache));
+    let flusher = CacheDB::spawn_flusher(&cache);
+
+    // La modification est sauvegardée par le thread, sans autre écriture
+    assert!(cache.lock().unwrap().put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(!Path::new(file_path).exists());
+    thread::sleep(Duration::from_millis(200));
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.peek(&"pomme".to_string()), Some(&"rouge".to_string()));
+    assert_eq!(reloaded.len(), 1);
+    drop(reloaded);
+
+    // Le thread s'arrête avec le cache
+    drop(cache);
+    flusher.join().unwrap();
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_flush_barrier() {
+    let file_path = "test_cache_flush_barrier.txt";
+    let log_path = "test_cache_flush_barrier.log";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_write_behind(Duration::from_secs(60), 100)
+        .with_operation_log(log_path)
+        .expect("Erreur lors de l'ouverture du journal");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(!Path::new(file_path).exists());
+
+    // flush_barrier sauvegarde les modifications en attente et réinitialise la persistance différée
+    let persist_count = cache.stats().persist_count;
+    assert!(cache.flush_barrier().is_ok());
+    assert_eq!(cache.stats().persist_count, persist_count + 1);
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.peek(&"pomme".to_string()), Some(&"rouge".to_string()));
+    drop(reloaded);
+    assert!(fs::read_to_string(log_path).unwrap().contains("pomme"));
+
+    drop(cache);
+
+    fs::remove_file(file_path).unwrap();
+    fs::remove_file(log_path).unwrap();
+}
+
+#[test]
+fn test_cache_snapshot_restore() {
+    let file_path = "test_cache_snapshot_restore.txt";
+    let snapshot_path = "test_cache_snapshot_restore.bak";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+
+    assert!(cache.put("1".to_string(), "un".to_string()).is_ok());
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.snapshot(snapshot_path).is_ok());
+
+    // Les modifications postérieures ne sont pas dans la copie
+    assert!(cache.remove(&"1".to_string()).is_ok());
+    assert!(cache.put("2".to_string(), "deux".to_string()).is_ok());
+
+    assert!(cache.restore_from(snapshot_path).is_ok());
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&"1".to_string()), Some(&"un".to_string()));
+    assert_eq!(cache.get(&"2".to_string()), None);
+
+    // Le contenu restauré est persisté
+    let mut reloaded: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.get(&"pomme".to_string()), Some(&"rouge".to_string()));
+
+    // Une copie inexistante laisse le cache inchangé
+    assert!(matches!(cache.restore_from("test_cache_snapshot_missing.bak"), Err(CustomError::NotFound)));
+    assert_eq!(cache.len(), 2);
+
+    fs::remove_file(file_path).unwrap();
+    fs::remove_file(snapshot_path).unwrap();
+}
+
+#[test]
+fn test_cache_export_import() {
+    let file_path = "test_cache_export_import.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+
+    assert!(cache.put("1".to_string(), "un, deux".to_string()).is_ok());
+    assert!(cache.put("pomme".to_string(), "\"rouge\"".to_string()).is_ok());
+
+    for format in [ExportFormat::Json, ExportFormat::Csv] {
+        let mut export = Vec::new();
+        assert!(cache.export_to_writer(&mut export, format).is_ok());
+
+        let import_path = "test_cache_export_import_target.txt";
+        let mut target: CacheDB<String, String> = CacheDB::new_persistent(5, import_path).expect("Erreur lors de la création du cache");
+        assert_eq!(target.import_from_reader(export.as_slice(), format).unwrap(), 2);
+
+        let exported: Vec<_> = cache.iter().collect();
+        let imported: Vec<_> = target.iter().collect();
+        assert_eq!(exported, imported);
+
+        fs::remove_file(import_path).unwrap();
+    }
+
+    // Un export invalide ne modifie pas le cache
+    assert!(matches!(cache.import_from_reader("invalid".as_bytes(), ExportFormat::Csv), Err(CustomError::BadRequest)));
+    assert!(cache.import_from_reader("invalid".as_bytes(), ExportFormat::Json).is_err());
+    assert_eq!(cache.len(), 2);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_max_bytes() {
+    let file_path = "test_cache_max_bytes.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(10, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_max_bytes(30);
+
+    // La taille d'un élément est celle de sa clé et de sa valeur en JSON : "1" + "un" = 3 + 4 octets
+    assert!(cache.put("1".to_string(), "un".to_string()).is_ok());
+    assert!(cache.put("2".to_string(), "deux".to_string()).is_ok());
+    assert!(cache.put("3".to_string(), "trois".to_string()).is_ok());
+    assert_eq!(cache.stats().used_bytes, 7 + 9 + 10);
+
+    // Le budget est dépassé : "1" est supprimé
+    assert!(cache.put("4".to_string(), "quatre".to_string()).is_ok());
+    assert_eq!(cache.get(&"1".to_string()), None);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.stats().used_bytes, 9 + 10 + 11);
+
+    // La mise à jour d'une clé remplace sa taille
+    assert!(cache.put("3".to_string(), "3".to_string()).is_ok());
+    assert_eq!(cache.stats().used_bytes, 9 + 11 + 6);
+
+    // Un élément plus grand que le budget est refusé
+    let result = cache.put("grand".to_string(), "x".repeat(40));
+    assert!(matches!(result, Err(CustomError::CacheDbCapacityError)));
+    assert_eq!(cache.len(), 3);
+
+    assert!(cache.remove(&"4".to_string()).is_ok());
+    assert_eq!(cache.stats().used_bytes, 9 + 6);
+    assert!(cache.clear().is_ok());
+    assert_eq!(cache.stats().used_bytes, 0);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_apply_patch() {
+    let file_path = "test_cache_apply_patch.txt";
+    let mut cache: CacheDB<String, Value> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+
+    let pomme = json!({"couleur": "verte", "origine": {"pays": "France", "region": "Normandie"}});
+    assert!(cache.put("pomme".to_string(), pomme).is_ok());
+    assert!(cache.put("banane".to_string(), json!("jaune")).is_ok());
+
+    let patch = json!({"couleur": "rouge", "origine": {"region": null}, "prix": 2});
+    assert!(cache.apply_patch(&"pomme".to_string(), &patch).is_ok());
+
+    // La valeur modifiée est persistée et devient la plus récemment utilisée
+    let mut reloaded: CacheDB<String, Value> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.iter().next_back().map(|(k, _)| k.as_str()), Some("pomme"));
+    assert_eq!(
+        reloaded.get(&"pomme".to_string()),
+        Some(&json!({"couleur": "rouge", "origine": {"pays": "France"}, "prix": 2}))
+    );
+
+    // Clé inconnue
+    assert!(matches!(cache.apply_patch(&"orange".to_string(), &patch), Err(CustomError::NotFound)));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_apply_patch_invalid_result() {
+    let file_path = "test_cache_apply_patch_invalid_result.txt";
+    let mut cache: CacheDB<String, i32> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+
+    assert!(cache.put("pomme".to_string(), 1).is_ok());
+
+    // Le résultat du patch n'est pas un entier : la valeur est inchangée
+    let result = cache.apply_patch(&"pomme".to_string(), &json!({"prix": 2}));
+    assert!(matches!(result, Err(CustomError::SerializationError(_))));
+    assert_eq!(cache.get(&"pomme".to_string()), Some(&1));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_metadata() {
+    let file_path = "test_cache_metadata.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+
+    assert!(cache.put("pomme".to_string(), "verte".to_string()).is_ok());
+    let created = cache.metadata(&"pomme".to_string()).unwrap();
+    assert_eq!(created.hit_count, 0);
+    assert_eq!(created.created_at, created.updated_at);
+
+    // Les lectures incrémentent le compteur
+    cache.get(&"pomme".to_string());
+    cache.get(&"pomme".to_string());
+    let read = cache.metadata(&"pomme".to_string()).unwrap();
+    assert_eq!(read.hit_count, 2);
+    assert!(read.last_accessed >= created.last_accessed);
+
+    // Une mise à jour conserve la date de création
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    let updated = cache.metadata(&"pomme".to_string()).unwrap();
+    assert_eq!(updated.created_at, created.created_at);
+    assert!(updated.updated_at >= created.updated_at);
+    assert_eq!(updated.hit_count, 2);
+
+    // Les métadonnées sont persistées
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.metadata(&"pomme".to_string()), Some(updated));
+    assert_eq!(reloaded.metadata(&"banane".to_string()), None);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_load_without_metadata() {
+    let file_path = "test_cache_load_without_metadata.txt";
+
+    // Fichier écrit avant l'ajout des métadonnées, avec des "=" dans les valeurs
+    fs::write(file_path, "\"1\"=\"un\"\n\"a=b\"=\"c=d\"\n").unwrap();
+
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&"a=b".to_string()), Some(&"c=d".to_string()));
+    assert_eq!(cache.metadata(&"1".to_string()).map(|metadata| metadata.hit_count), Some(0));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Fruit {
+    couleur: String,
+    prix: u32,
+}
+
+#[test]
+fn test_typed_cache() {
+    let file_path = "test_typed_cache.txt";
+    let mut cache: TypedCache<Fruit> = TypedCache::new_persistent(3, file_path, 1).expect("Erreur lors de la création du cache");
+
+    let pomme = Fruit { couleur: "rouge".to_string(), prix: 2 };
+    assert!(cache.put("pomme".to_string(), &pomme).is_ok());
+    assert_eq!(cache.get("pomme").unwrap(), Some(pomme));
+    assert_eq!(cache.get("banane").unwrap(), None);
+    drop(cache);
+
+    // Une valeur écrite avec une autre version de schéma est signalée
+    let mut cache: TypedCache<Fruit> = TypedCache::new_persistent(3, file_path, 2).expect("Erreur lors de la création du cache");
+    assert!(matches!(
+        cache.get("pomme"),
+        Err(CustomError::SchemaVersionMismatch { expected: 2, found: 1 })
+    ));
+
+    assert!(cache.remove("pomme").is_ok());
+    assert!(cache.inner().is_empty());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_get_many() {
+    let file_path = "test_cache_get_many.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+
+    assert!(cache.put("1".to_string(), "un".to_string()).is_ok());
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+
+    let keys = vec!["pomme".to_string(), "banane".to_string(), "1".to_string(), "pomme".to_string()];
+    let results = cache.get_many(&keys);
+
+    // Un résultat par clé demandée, dans le même ordre
+    assert_eq!(results.len(), keys.len());
+    for (key, item) in keys.iter().zip(results.iter()) {
+        assert_eq!(&item.key, key);
+    }
+    assert_eq!(results[0].status, BatchStatus::Found("rouge".to_string()));
+    assert_eq!(results[1].status, BatchStatus::Missing);
+    assert_eq!(results[2].status, BatchStatus::Found("un".to_string()));
+    assert_eq!(results[3].status, BatchStatus::Found("rouge".to_string()));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_migrations() {
+    let file_path = "test_cache_migrations.txt";
+
+    // Fichier écrit avant l'ajout des migrations : aucun historique
+    fs::write(file_path, "\"1\"=\"un\"\n").unwrap();
+
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    let history = cache.migration_history().to_vec();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].version, 1);
+    assert_eq!(history[0].name, "add_entry_metadata");
+    assert_eq!(history[1].version, 2);
+    assert_eq!(history[1].name, "add_line_checksums");
+    drop(cache);
+
+    // L'historique est persisté et les migrations ne sont pas rejouées
+    let contents = fs::read_to_string(file_path).unwrap();
+    assert!(contents.starts_with("#migration="));
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.migration_history(), history.as_slice());
+    assert!(cache.metadata(&"1".to_string()).is_some());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_freeze() {
+    let file_path = "test_cache_freeze.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.freeze(&"pomme".to_string()).is_ok());
+    assert!(matches!(cache.freeze(&"banane".to_string()), Err(CustomError::NotFound)));
+
+    // Les écritures sur une clé gelée sont refusées
+    assert!(matches!(cache.put("pomme".to_string(), "verte".to_string()), Err(CustomError::Locked)));
+    assert!(matches!(cache.remove(&"pomme".to_string()), Err(CustomError::Locked)));
+    assert!(matches!(cache.apply_patch(&"pomme".to_string(), &json!("verte")), Err(CustomError::Locked)));
+    assert_eq!(cache.get(&"pomme".to_string()), Some(&"rouge".to_string()));
+
+    // L'état gelé est persisté
+    let mut reloaded: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert!(matches!(reloaded.put("pomme".to_string(), "verte".to_string()), Err(CustomError::Locked)));
+    drop(reloaded);
+
+    assert!(cache.unfreeze(&"pomme".to_string()).is_ok());
+    assert!(cache.put("pomme".to_string(), "verte".to_string()).is_ok());
+    assert!(cache.remove(&"pomme".to_string()).is_ok());
+
+    // Un élément expiré ne peut plus être gelé ni dégelé, et ne bloque pas un import
+    assert!(cache.put_with_ttl("kiwi".to_string(), "vert".to_string(), None, Some(Duration::from_millis(50))).is_ok());
+    assert!(cache.put_with_ttl("figue".to_string(), "violette".to_string(), None, Some(Duration::from_millis(50))).is_ok());
+    assert!(cache.freeze(&"figue".to_string()).is_ok());
+    thread::sleep(Duration::from_millis(100));
+    assert!(matches!(cache.freeze(&"kiwi".to_string()), Err(CustomError::NotFound)));
+    let import = r#"[{"key":"figue","value":"noire"}]"#;
+    assert_eq!(cache.import_from_reader(import.as_bytes(), ExportFormat::Json).unwrap(), 1);
+    assert_eq!(cache.get(&"figue".to_string()), Some(&"noire".to_string()));
+    assert!(!cache.metadata(&"figue".to_string()).unwrap().frozen);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+struct EventRecorder(Arc<Mutex<Vec<String>>>);
+
+impl CacheEventListener<String, String> for EventRecorder {
+    fn on_insert(&self, key: &String, value: &String) {
+        self.0.lock().unwrap().push(format!("insert {}={}", key, value));
+    }
+
+    fn on_remove(&self, key: &String, value: &String) {
+        self.0.lock().unwrap().push(format!("remove {}={}", key, value));
+    }
+
+    fn on_evict(&self, key: &String, value: &String) {
+        self.0.lock().unwrap().push(format!("evict {}={}", key, value));
+    }
+
+    fn on_expire(&self, key: &String, value: &String) {
+        self.0.lock().unwrap().push(format!("expire {}={}", key, value));
+    }
+
+    fn on_corrupted(&self, key: &String, value: &String) {
+        self.0.lock().unwrap().push(format!("corrupted {}={}", key, value));
+    }
+}
+
+#[test]
+fn test_cache_subscribe() {
+    let file_path = "test_cache_subscribe.txt";
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    cache.subscribe(EventRecorder(events.clone()));
+
+    assert!(cache.put("1".to_string(), "un".to_string()).is_ok());
+    assert!(cache.put("2".to_string(), "deux".to_string()).is_ok());
+    assert!(cache.put("3".to_string(), "trois".to_string()).is_ok()); // Évince "1"
+    assert!(cache.remove(&"2".to_string()).is_ok());
+    assert!(cache.put("3".to_string(), "trois-bis".to_string()).is_ok());
+    assert!(cache.clear().is_ok());
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            "insert 1=un",
+            "insert 2=deux",
+            "evict 1=un",
+            "insert 3=trois",
+            "remove 2=deux",
+            "insert 3=trois-bis",
+            "remove 3=trois-bis",
+        ]
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_resp_server() {
+    let file_path = "test_resp_server.txt";
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(10, file_path).expect("Erreur lors de la création du cache");
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || resp::serve(listener, Arc::new(Mutex::new(cache))));
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut send = |command: &str, lines: usize| -> String {
+        stream.write_all(command.as_bytes()).unwrap();
+        let mut reply = String::new();
+        for _ in 0..lines {
+            reader.read_line(&mut reply).unwrap();
+        }
+        reply
+    };
+
+    assert_eq!(send("PING\r\n", 1), "+PONG\r\n");
+    assert_eq!(send("*3\r\n$3\r\nSET\r\n$8\r\nuser:1=a\r\n$5\r\nalice\r\n", 1), "+OK\r\n");
+    assert_eq!(send("SET user:2 bob\r\n", 1), "+OK\r\n");
+    assert_eq!(send("SET pomme rouge\r\n", 1), "+OK\r\n");
+    assert_eq!(send("SETNX pomme verte\r\n", 1), ":0\r\n");
+    assert_eq!(send("*2\r\n$3\r\nGET\r\n$8\r\nuser:1=a\r\n", 2), "$5\r\nalice\r\n");
+    assert_eq!(send("GET banane\r\n", 1), "$-1\r\n");
+    assert_eq!(send("MGET pomme banane\r\n", 4), "*2\r\n$5\r\nrouge\r\n$-1\r\n");
+    assert_eq!(send("KEYS user:*\r\n", 5), "*2\r\n$6\r\nuser:2\r\n$8\r\nuser:1=a\r\n");
+    assert_eq!(send("DEL user:2 banane\r\n", 1), ":1\r\n");
+    assert_eq!(send("TTL pomme\r\n", 1), ":-1\r\n");
+    assert_eq!(send("EXPIRE pomme 60\r\n", 1), ":1\r\n");
+    assert_eq!(send("TTL pomme\r\n", 1), ":60\r\n");
+    assert_eq!(send("EXPIRE banane 60\r\n", 1), ":0\r\n");
+    assert_eq!(send("TTL banane\r\n", 1), ":-2\r\n");
+    assert_eq!(send("TOUCH pomme banane\r\n", 1), ":1\r\n");
+    assert_eq!(send("GET\r\n", 1), "-ERR wrong number of arguments for 'GET' command\r\n");
+    assert_eq!(send("FLUSHALL\r\n", 1), "-ERR unknown command 'FLUSHALL'\r\n");
+    assert_eq!(send("QUIT\r\n", 1), "+OK\r\n");
+
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(10, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.len(), 2);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_resp_mget() {
+    let file_path = "test_resp_mget.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(10, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put_with_ttl("kiwi".to_string(), "vert".to_string(), None, Some(Duration::from_millis(50))).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || resp::serve(listener, Arc::new(Mutex::new(cache))));
+    thread::sleep(Duration::from_millis(100));
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    // Les clés expirées mais pas encore supprimées ne sont pas listées
+    stream.write_all(b"KEYS *\r\n").unwrap();
+    let mut reply = String::new();
+    for _ in 0..5 {
+        reader.read_line(&mut reply).unwrap();
+    }
+    assert_eq!(reply, "*2\r\n$5\r\npomme\r\n$6\r\nbanane\r\n");
+
+    // Les valeurs sont retournées dans l'ordre des clés, avec nil pour les clés absentes ou expirées
+    stream.write_all(b"MGET banane absente kiwi pomme banane\r\n").unwrap();
+    let mut reply = String::new();
+    for _ in 0..9 {
+        reader.read_line(&mut reply).unwrap();
+    }
+    assert_eq!(reply, "*5\r\n$5\r\njaune\r\n$-1\r\n$-1\r\n$5\r\nrouge\r\n$5\r\njaune\r\n");
+
+    fs::remove_file(file_path).ok();
+}
+
+#[test]
+fn test_resp_pubsub() {
+    let file_path = "test_resp_pubsub.txt";
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(10, file_path).expect("Erreur lors de la création du cache");
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || resp::serve(listener, Arc::new(Mutex::new(cache))));
+
+    let read = |reader: &mut BufReader<TcpStream>, lines: usize| -> String {
+        let mut reply = String::new();
+        for _ in 0..lines {
+            reader.read_line(&mut reply).unwrap();
+        }
+        reply
+    };
+
+    // Un premier client s'abonne à deux canaux
+    let mut subscriber = TcpStream::connect(address).unwrap();
+    let mut subscriber_reader = BufReader::new(subscriber.try_clone().unwrap());
+    subscriber.write_all(b"SUBSCRIBE invalidations alertes\r\n").unwrap();
+    assert_eq!(read(&mut subscriber_reader, 6), "*3\r\n$9\r\nsubscribe\r\n$13\r\ninvalidations\r\n:1\r\n");
+    assert_eq!(read(&mut subscriber_reader, 6), "*3\r\n$9\r\nsubscribe\r\n$7\r\nalertes\r\n:2\r\n");
+
+    // Un second client publie
+    let mut publisher = TcpStream::connect(address).unwrap();
+    let mut publisher_reader = BufReader::new(publisher.try_clone().unwrap());
+    publisher.write_all(b"PUBLISH invalidations pomme\r\n").unwrap();
+    assert_eq!(read(&mut publisher_reader, 1), ":1\r\n");
+    publisher.write_all(b"PUBLISH vide message\r\n").unwrap();
+    assert_eq!(read(&mut publisher_reader, 1), ":0\r\n");
+    publisher.write_all(b"PUBLISH invalidations\r\n").unwrap();
+    assert_eq!(read(&mut publisher_reader, 1), "-ERR wrong number of arguments for 'PUBLISH' command\r\n");
+
+    assert_eq!(read(&mut subscriber_reader, 7), "*3\r\n$7\r\nmessage\r\n$13\r\ninvalidations\r\n$5\r\npomme\r\n");
+
+    // La fermeture de la connexion abonnée la retire des canaux, sans attendre de publication
+    drop(subscriber_reader);
+    drop(subscriber);
+    thread::sleep(Duration::from_millis(300));
+    publisher.write_all(b"PUBLISH alertes test\r\n").unwrap();
+    assert_eq!(read(&mut publisher_reader, 1), ":0\r\n");
+
+    fs::remove_file(file_path).ok();
+}
+
+#[test]
+fn test_resp_protocol_limits() {
+    let file_path = "test_resp_protocol_limits.txt";
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(10, file_path).expect("Erreur lors de la création du cache");
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || resp::serve(listener, Arc::new(Mutex::new(cache))));
+
+    // Chaque en-tête invalide est rejeté par une erreur de protocole, puis la connexion est fermée
+    let headers = [
+        "*999999999999\r\n",
+        "*1\r\n$18446744073709551615\r\n",
+        "*1\r\n$18446744073709551616\r\n",
+        "*1\r\n$536870913\r\n",
+        "*-1\r\n",
+    ];
+    for header in headers {
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(header.as_bytes()).unwrap();
+
+        let mut reply = String::new();
+        reader.read_line(&mut reply).unwrap();
+        assert_eq!(reply, "-ERR Protocol error\r\n");
+        assert_eq!(reader.read_line(&mut reply).unwrap(), 0);
+    }
+
+    // Une ligne sans terminateur dans les 64 Kio est rejetée sans attendre la suite
+    let mut stream = TcpStream::connect(address).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    stream.write_all(&[b'a'; 64 * 1024]).unwrap();
+    let mut reply = String::new();
+    reader.read_line(&mut reply).unwrap();
+    assert_eq!(reply, "-ERR Protocol error\r\n");
+    assert_eq!(reader.read_line(&mut reply).unwrap(), 0);
+
+    // Une commande de taille raisonnable reste acceptée
+    let mut stream = TcpStream::connect(address).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    stream.write_all(b"*1\r\n$4\r\nPING\r\n").unwrap();
+    let mut reply = String::new();
+    reader.read_line(&mut reply).unwrap();
+    assert_eq!(reply, "+PONG\r\n");
+
+    fs::remove_file(file_path).ok();
+}
+
+#[test]
+fn test_cache_operation_log_replay() {
+    let file_path = "test_cache_operation_log.txt";
+    let log_path = "test_cache_operation_log.log";
+    let replay_path = "test_cache_operation_log_replay.txt";
+    let mut cache: CacheDB<String, Value> = CacheDB::new_persistent(3, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_operation_log(log_path)
+        .expect("Erreur lors de l'ouverture du journal");
+
+    assert!(cache.put("1".to_string(), json!("un")).is_ok());
+    assert!(cache.put("pomme".to_string(), json!({"couleur": "verte"})).is_ok());
+    assert!(cache.apply_patch(&"pomme".to_string(), &json!({"couleur": "rouge"})).is_ok());
+    assert!(cache.freeze(&"pomme".to_string()).is_ok());
+    assert!(cache.put("pomme".to_string(), json!("refusé")).is_err()); // Non enregistré
+    assert!(cache.remove(&"1".to_string()).is_ok());
+    assert!(cache.put("2".to_string(), json!("deux")).is_ok());
+
+    let mut replayed: CacheDB<String, Value> = CacheDB::new_persistent(3, replay_path).expect("Erreur lors de la création du cache");
+    assert_eq!(replayed.replay(log_path).unwrap(), 6);
+
+    let original: Vec<_> = cache.iter().collect();
+    let reproduced: Vec<_> = replayed.iter().collect();
+    assert_eq!(original, reproduced);
+    assert!(replayed.metadata(&"pomme".to_string()).unwrap().frozen);
+
+    fs::remove_file(file_path).unwrap();
+    fs::remove_file(log_path).unwrap();
+    fs::remove_file(replay_path).unwrap();
+}
+
+#[test]
+fn test_cache_load_preserves_newer_format_data() {
+    let file_path = "test_cache_load_preserves_newer_format_data.txt";
+
+    // Fichier écrit par une version plus récente : migration, en-tête, métadonnées et champ inconnus
+    let metadata = r#"{"created_at":{"secs_since_epoch":1,"nanos_since_epoch":0},"updated_at":{"secs_since_epoch":1,"nanos_since_epoch":0},"last_accessed":{"secs_since_epoch":1,"nanos_since_epoch":0},"hit_count":3,"ttl":60}"#;
+    let contents = format!(
+        "#migration={{\"version\":1,\"name\":\"add_entry_metadata\",\"applied_at\":{{\"secs_since_epoch\":1,\"nanos_since_epoch\":0}}}}\n\
+         #migration={{\"version\":3,\"name\":\"future\",\"applied_at\":{{\"secs_since_epoch\":2,\"nanos_since_epoch\":0}}}}\n\
+         #checksum=\"abc\"\n\
+         \"pomme\"=\"rouge\"={}=[\"tag\"]\n",
+        metadata
+    );
+    fs::write(file_path, contents).unwrap();
+
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.metadata(&"pomme".to_string()).unwrap().hit_count, 3);
+    assert_eq!(cache.migration_history().len(), 2);
+
+    // Les données inconnues sont réécrites lors de la sauvegarde
+    assert!(cache.put("pomme".to_string(), "verte".to_string()).is_ok());
+    let saved = fs::read_to_string(file_path).unwrap();
+    assert!(saved.contains("\"name\":\"future\""));
+    assert!(saved.contains("#checksum=\"abc\"\n"));
+    assert!(saved.contains("\"ttl\":60"));
+    assert!(saved.contains("\"pomme\"=\"verte\"="));
+    assert!(saved.contains("=[\"tag\"]=crc32:"));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cachectl() {
+    let file_path = "test_cachectl.txt";
+    let cachectl = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_cachectl")).arg(file_path).args(args).output().unwrap();
+
+    assert!(cachectl(&["put", "pomme", "rouge"]).status.success());
+    assert!(cachectl(&["put", "poire", "{\"couleur\":\"verte\"}"]).status.success());
+
+    let output = cachectl(&["get", "pomme"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "\"rouge\"\n");
+
+    let output = cachectl(&["ls", "po"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "pomme = \"rouge\"\npoire = {\"couleur\":\"verte\"}\n");
+
+    // Une clé absente ou une commande inconnue se terminent par une erreur
+    assert!(cachectl(&["rm", "pomme"]).status.success());
+    assert!(!cachectl(&["get", "pomme"]).status.success());
+    assert!(!cachectl(&["inconnue"]).status.success());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_soft_and_hard_ttl() {
+    let file_path = "test_cache_soft_and_hard_ttl.txt";
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    cache.subscribe(EventRecorder(events.clone()));
+
+    // Durée de vie souple écoulée : l'élément est périmé mais toujours lisible
+    assert!(cache
+        .put_with_ttl("pomme".to_string(), "rouge".to_string(), Some(Duration::ZERO), Some(Duration::from_secs(3600)))
+        .is_ok());
+    let metadata = cache.metadata(&"pomme".to_string()).unwrap();
+    assert!(metadata.is_stale());
+    assert!(!metadata.is_expired());
+    assert_eq!(cache.get(&"pomme".to_string()), Some(&"rouge".to_string()));
+
+    // Durée de vie stricte écoulée : l'élément n'est plus chargé ni retourné
+    assert!(cache.put_with_ttl("banane".to_string(), "jaune".to_string(), None, Some(Duration::ZERO)).is_ok());
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.len(), 1);
+    assert_eq!(reloaded.metadata(&"pomme".to_string()).unwrap().soft_ttl, Some(Duration::ZERO));
+
+    assert_eq!(cache.get(&"banane".to_string()), None);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(*events.lock().unwrap(), vec!["insert pomme=rouge", "insert banane=jaune", "expire banane=jaune"]);
+
+    // Supprimer les durées de vie rend l'élément permanent
+    assert!(cache.expire(&"pomme".to_string(), None, None).is_ok());
+    assert!(!cache.metadata(&"pomme".to_string()).unwrap().is_stale());
+    assert!(matches!(cache.expire(&"banane".to_string(), None, None), Err(CustomError::NotFound)));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cachectl_simulate() {
+    let trace_path = "test_cachectl_simulate.log";
+    fs::write(trace_path, "a\nb\na\nc\na\nb\nd\na\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cachectl"))
+        .args(["simulate", "--trace", trace_path, "--capacity", "2", "--policy", "lru,lfu"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // LFU conserve "a", la clé la plus fréquente, lorsque "d" est inséré
+    let report = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("lru") && lines[1].ends_with("25.0%"));
+    assert!(lines[2].starts_with("lfu") && lines[2].ends_with("37.5%"));
+
+    fs::remove_file(trace_path).unwrap();
+}
+
+#[test]
+fn test_resp_sweeper() {
+    let file_path = "test_resp_sweeper.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put_with_ttl("pomme".to_string(), "rouge".to_string(), None, Some(Duration::from_millis(50))).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+
+    let cache = Arc::new(Mutex::new(cache));
+    let sweeper = resp::spawn_sweeper(&cache, Duration::from_millis(10));
+    thread::sleep(Duration::from_millis(200));
+
+    // L'élément expiré est supprimé sans être lu, et la suppression est persistée
+    assert_eq!(cache.lock().unwrap().len(), 1);
+    assert!(!fs::read_to_string(file_path).unwrap().contains("pomme"));
+
+    // Le thread s'arrête lorsque le cache n'est plus référencé
+    drop(cache);
+    sweeper.join().unwrap();
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_backing_store() {
+    let file_path = "test_cache_backing_store.txt";
+    let database = Arc::new(Mutex::new(HashMap::from([("pomme".to_string(), "rouge".to_string())])));
+    let (reader, writer) = (database.clone(), database.clone());
+    let store = FnBackingStore::new(
+        move |key: &String| Ok(reader.lock().unwrap().get(key).cloned()),
+        move |key: &String, value: &String| {
+            if value.is_empty() {
+                return Err(CustomError::BadRequest);
+            }
+            writer.lock().unwrap().insert(key.clone(), value.clone());
+            Ok(())
+        },
+    );
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_backing_store(store);
+
+    // Lecture directe : la clé absente est chargée depuis la source
+    assert_eq!(cache.get(&"pomme".to_string()), Some(&"rouge".to_string()));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&"banane".to_string()), None);
+
+    // Écriture directe : la source est modifiée avant le cache
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert_eq!(database.lock().unwrap().get("banane"), Some(&"jaune".to_string()));
+
+    // Une erreur de la source annule l'insertion
+    assert!(matches!(cache.put("orange".to_string(), String::new()), Err(CustomError::BadRequest)));
+    assert_eq!(cache.len(), 2);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_cache_put_remove_return_previous_value() {
+    let file_path = "test_cache_put_remove_return_previous_value.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+
+    assert_eq!(cache.put("pomme".to_string(), "verte".to_string()).unwrap(), None);
+    assert_eq!(cache.put("pomme".to_string(), "rouge".to_string()).unwrap(), Some("verte".to_string()));
+    assert_eq!(cache.remove(&"pomme".to_string()).unwrap(), "rouge".to_string());
+    assert!(matches!(cache.remove(&"pomme".to_string()), Err(CustomError::NotFound)));
+
+    // Les anciennes méthodes restent disponibles
+    assert!(cache.set("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.delete(&"banane".to_string()).is_ok());
+    assert!(cache.is_empty());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_tiered_cache() {
+    let file_path = "test_tiered_cache.txt";
+    let mut cache: TieredCache<String, String> = TieredCache::new_persistent(2, 10, file_path).expect("Erreur lors de la création du cache");
+
+    assert!(cache.put("1".to_string(), "un".to_string()).is_ok());
+    assert!(cache.put("2".to_string(), "deux".to_string()).is_ok());
+    assert!(cache.put("3".to_string(), "trois".to_string()).is_ok()); // Rétrograde "1"
+    assert_eq!(cache.stats().hot_len, 2);
+    assert_eq!(cache.cold().len(), 1);
+
+    // Promotion de "1", qui rétrograde "2"
+    assert_eq!(cache.get(&"1".to_string()).unwrap(), Some(&"un".to_string()));
+    assert_eq!(cache.get(&"1".to_string()).unwrap(), Some(&"un".to_string()));
+    assert_eq!(cache.get(&"4".to_string()).unwrap(), None);
+    assert_eq!(cache.cold().iter().collect::<Vec<_>>(), vec![(&"2".to_string(), &"deux".to_string())]);
+
+    let stats = cache.stats();
+    assert_eq!((stats.hot_hits, stats.cold_hits, stats.misses), (1, 1, 1));
+    assert_eq!(cache.len(), 3);
+
+    // Une valeur remplacée dans le niveau persistant est retournée
+    assert_eq!(cache.put("2".to_string(), "deux-bis".to_string()).unwrap(), Some("deux".to_string()));
+    assert_eq!(cache.remove(&"3".to_string()).unwrap(), "trois".to_string());
+
+    // Le niveau en mémoire est sauvegardé à la destruction du cache
+    drop(cache);
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(10, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.len(), 2);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_tiered_cache_promotion_is_silent() {
+    let file_path = "test_tiered_cache_promotion_is_silent.txt";
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cold: CacheDB<String, String> = CacheDB::new_persistent(10, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_soft_delete(Duration::from_secs(60));
+    cold.subscribe(EventRecorder(events.clone()));
+    let mut cache = TieredCache::from_cache(1, cold);
+    assert!(cache.put("1".to_string(), "un".to_string()).is_ok());
+    assert!(cache.put("2".to_string(), "deux".to_string()).is_ok()); // Rétrograde "1"
+    let persist_count = cache.stats().cold.persist_count;
+
+    // La promotion de "1" ne notifie pas les observateurs et ne laisse pas d'élément supprimé ;
+    // seule la rétrogradation de "2" est persistée
+    assert_eq!(cache.get(&"1".to_string()).unwrap(), Some(&"un".to_string()));
+    assert_eq!(*events.lock().unwrap(), vec!["insert 1=un", "insert 2=deux"]);
+    assert_eq!(cache.stats().cold.persist_count, persist_count + 1);
+    let contents = fs::read_to_string(file_path).unwrap();
+    assert_eq!(contents.lines().filter(|line| !line.starts_with('#')).count(), 1);
+
+    drop(cache);
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_capacity_policy() {
+    let file_path = "test_cache_capacity_policy.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("1".to_string(), "un".to_string()).is_ok());
+    assert!(cache.put("2".to_string(), "deux".to_string()).is_ok());
+    assert!(cache.put("3".to_string(), "trois".to_string()).is_ok());
+    drop(cache);
+
+    // Le fichier n'est pas modifié lorsque le chargement est refusé
+    let result: Result<CacheDB<String, String>, CustomError> = CacheDB::new_persistent_with_policy(2, file_path, CapacityPolicy::Error);
+    assert!(matches!(result, Err(CustomError::CacheDbCapacityError)));
+
+    let cache: CacheDB<String, String> =
+        CacheDB::new_persistent_with_policy(2, file_path, CapacityPolicy::Grow).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.stats().capacity, 3);
+    drop(cache);
+
+    // Par défaut, les éléments les moins récemment utilisés sont supprimés
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), vec!["2", "3"]);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_file_lock() {
+    let file_path = "test_cache_file_lock.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_file_lock(LockMode::Exclusive)
+        .expect("Erreur lors du verrouillage du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+
+    // Le fichier est déjà verrouillé en mode exclusif
+    let result = CacheDB::<String, String>::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_file_lock(LockMode::Shared);
+    assert!(matches!(result, Err(CustomError::CacheDbLockError)));
+    drop(cache);
+
+    // Plusieurs lecteurs peuvent partager le fichier, mais ne peuvent pas le modifier
+    let mut reader: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_file_lock(LockMode::Shared)
+        .expect("Erreur lors du verrouillage du cache");
+    let other_reader = CacheDB::<String, String>::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_file_lock(LockMode::Shared);
+    assert!(other_reader.is_ok());
+    assert_eq!(reader.get(&"pomme".to_string()), Some(&"rouge".to_string()));
+    assert!(matches!(reader.put("banane".to_string(), "jaune".to_string()), Err(CustomError::CacheDbLockError)));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_read_only_rejects_writes() {
+    let file_path = "test_cache_read_only_rejects_writes.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    drop(cache);
+
+    let mut reader: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_file_lock(LockMode::Shared)
+        .expect("Erreur lors du verrouillage du cache");
+    let events = Arc::new(Mutex::new(Vec::new()));
+    reader.subscribe(EventRecorder(events.clone()));
+    let pomme = "pomme".to_string();
+
+    // Chaque modification est refusée avant de toucher au cache
+    assert!(matches!(reader.put("banane".to_string(), "jaune".to_string()), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.put(pomme.clone(), "verte".to_string()), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.put_with_ttl(pomme.clone(), "verte".to_string(), None, None), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.put_if_absent("banane".to_string(), "jaune".to_string()), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.put_if_version(pomme.clone(), "verte".to_string(), 1), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.remove(&pomme), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.clear(), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.retain(|_, _| false), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.transaction(|transaction| { transaction.remove(pomme.clone()); }), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.freeze(&pomme), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.pin(&pomme), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.expire(&pomme, None, Some(Duration::from_secs(60))), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.touch(&pomme), Err(CustomError::CacheDbLockError)));
+    assert!(matches!(reader.restore(&pomme), Err(CustomError::CacheDbLockError)));
+    let extended = panic::catch_unwind(panic::AssertUnwindSafe(|| reader.extend([("banane".to_string(), "jaune".to_string())])));
+    assert!(extended.is_err());
+
+    assert_eq!(reader.len(), 1);
+    assert_eq!(reader.peek(&pomme), Some(&"rouge".to_string()));
+    let metadata = reader.metadata(&pomme).unwrap();
+    assert_eq!(metadata.version, 1);
+    assert!(!metadata.frozen && !metadata.pinned && metadata.hard_ttl.is_none());
+    assert!(events.lock().unwrap().is_empty());
+    drop(reader);
+
+    let reloaded: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(reloaded.len(), 1);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_value_checksum() {
+    let file_path = "test_cache_value_checksum.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert_eq!(cache.metadata(&"pomme".to_string()).unwrap().checksum, Some(0x4455_e890));
+    drop(cache);
+
+    // Altère la valeur de "pomme" sans mettre à jour sa somme de contrôle, dans un fichier sans
+    // sommes de contrôle par ligne
+    let contents: String = fs::read_to_string(file_path)
+        .unwrap()
+        .replace("\"rouge\"", "\"rougf\"")
+        .lines()
+        .filter(|line| !line.contains("add_line_checksums"))
+        .map(|line| format!("{}\n", line.split("=crc32:").next().unwrap()))
+        .collect();
+    fs::write(file_path, contents).unwrap();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    cache.subscribe(EventRecorder(events.clone()));
+
+    assert!(matches!(cache.try_get(&"pomme".to_string()), Err(CustomError::ValueCorrupted)));
+    assert_eq!(cache.get(&"pomme".to_string()), None);
+    assert_eq!(cache.get(&"banane".to_string()), Some(&"jaune".to_string()));
+    assert_eq!(*events.lock().unwrap(), vec!["corrupted pomme=rougf"]);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_line_checksum() {
+    let file_path = "test_cache_line_checksum.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    drop(cache);
+
+    // Chaque élément est suivi de la somme de contrôle de sa ligne
+    let contents = fs::read_to_string(file_path).unwrap();
+    assert!(contents.lines().filter(|line| !line.starts_with('#')).all(|line| line.contains("=crc32:")));
+
+    // Une ligne altérée est signalée avec son numéro
+    fs::write(file_path, contents.replace("\"jaune\"", "\"jaunf\"")).unwrap();
+    let result: Result<CacheDB<String, String>, CustomError> = CacheDB::new_persistent(5, file_path);
+    assert!(matches!(result, Err(CustomError::CacheDbCorruptedError { line: 4 })));
+
+    // Une ligne dont la somme de contrôle a été retirée est aussi signalée
+    let truncated: String = contents
+        .lines()
+        .map(|line| match line.contains("\"jaune\"") {
+            true => format!("{}\n", line.split("=crc32:").next().unwrap()),
+            false => format!("{}\n", line),
+        })
+        .collect();
+    fs::write(file_path, &truncated).unwrap();
+    let result: Result<CacheDB<String, String>, CustomError> = CacheDB::new_persistent(5, file_path);
+    assert!(matches!(result, Err(CustomError::CacheDbCorruptedError { line: 4 })));
+
+    // Sans la migration des sommes de contrôle, un fichier ancien sans sommes de contrôle reste lisible
+    let legacy: String = truncated
+        .lines()
+        .filter(|line| !line.contains("add_line_checksums"))
+        .map(|line| format!("{}\n", line.split("=crc32:").next().unwrap()))
+        .collect();
+    fs::write(file_path, legacy).unwrap();
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 2);
+    drop(cache);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_persist_metrics() {
+    let file_path = "test_cache_persist_metrics.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    let initial_count = cache.stats().persist_count;
+
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    let stats = cache.stats();
+    assert_eq!(stats.persist_count, initial_count + 1);
+    assert!(stats.last_persist_duration.is_some());
+    assert!(stats.total_persist_duration >= stats.last_persist_duration.unwrap());
+    assert_eq!(stats.file_size, Some(fs::metadata(file_path).unwrap().len()));
+
+    // Un instantané n'est pas compté comme une sauvegarde
+    cache.snapshot("test_cache_persist_metrics.bak").unwrap();
+    assert_eq!(cache.stats().persist_count, initial_count + 1);
+
+    fs::remove_file(file_path).unwrap();
+    fs::remove_file("test_cache_persist_metrics.bak").unwrap();
+}
+
+#[test]
+fn test_cache_load_lenient() {
+    let file_path = "test_cache_load_lenient.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    drop(cache);
+
+    // Altère une ligne et ajoute une ligne illisible
+    let contents = fs::read_to_string(file_path).unwrap().replace("\"jaune\"", "\"jaunf\"") + "illisible\n";
+    fs::write(file_path, contents).unwrap();
+
+    let result: Result<CacheDB<String, String>, CustomError> = CacheDB::new_persistent(5, file_path);
+    assert!(matches!(result, Err(CustomError::CacheDbCorruptedError { line: 4 })));
+
+    // Le mode tolérant ignore les deux lignes et les décrit dans le rapport
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent_lenient(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.get(&"pomme".to_string()), Some(&"rouge".to_string()));
+    assert_eq!(cache.get(&"banane".to_string()), None);
+    let report = cache.recovery_report().unwrap();
+    assert_eq!(report.skipped.len(), 2);
+    assert!(matches!(report.skipped[0].error, CustomError::CacheDbCorruptedError { line: 4 }));
+    assert!(report.skipped[0].content.contains("\"jaunf\""));
+    assert_eq!(report.skipped[1].line, 5);
+    assert_eq!(report.skipped[1].content, "illisible");
+    drop(cache);
+
+    // Le fichier réécrit ne contient plus que les lignes valides
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.load_lenient().unwrap().is_clean());
+    assert_eq!(cache.len(), 1);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_save_as() {
+    let file_path = "test_cache_save_as.txt";
+    let new_path = "test_cache_save_as.moved";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_file_lock(LockMode::Exclusive)
+        .expect("Erreur lors du verrouillage du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+
+    // Le fichier est déplacé et l'ancien supprimé
+    cache.save_as(new_path).unwrap();
+    assert!(!Path::new(file_path).exists());
+
+    // Le verrou suit le fichier
+    let locked: Result<CacheDB<String, String>, CustomError> = CacheDB::new_persistent(5, new_path).and_then(|cache| cache.with_file_lock(LockMode::Exclusive));
+    assert!(matches!(locked, Err(CustomError::CacheDbLockError)));
+
+    // Les modifications suivantes sont écrites dans le nouveau fichier
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    drop(cache);
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, new_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 2);
+
+    // L'échec de la suppression de l'ancien fichier n'annule pas le déplacement
+    fs::remove_file(new_path).unwrap();
+    cache.save_as(file_path).unwrap();
+    drop(cache);
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 2);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_shadow_writer() {
+    let file_path = "test_cache_shadow_writer.txt";
+    let shadow_path = "test_cache_shadow_writer_secondary.txt";
+    let secondary: CacheDB<String, String> = CacheDB::new_persistent(5, shadow_path).expect("Erreur lors de la création du cache");
+    let secondary = Arc::new(Mutex::new(secondary));
+    let shadow = ShadowWriter::new(secondary.clone());
+
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    cache.subscribe(shadow.clone());
+
+    // Les insertions, évictions et suppressions sont reproduites
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.put("kiwi".to_string(), "vert".to_string()).is_ok());
+    assert!(cache.remove(&"banane".to_string()).is_ok());
+    assert!(shadow.divergence(&cache).is_consistent());
+    assert_eq!(secondary.lock().unwrap().len(), 1);
+
+    // Les différences entre les deux caches sont signalées
+    secondary.lock().unwrap().put("kiwi".to_string(), "jaune".to_string()).unwrap();
+    secondary.lock().unwrap().put("poire".to_string(), "verte".to_string()).unwrap();
+    let report = shadow.divergence(&cache);
+    assert_eq!(report.mismatched, vec!["kiwi".to_string()]);
+    assert_eq!(report.unexpected, vec!["poire".to_string()]);
+    assert!(report.missing.is_empty());
+
+    // Une écriture refusée par le cache secondaire n'interrompt pas le cache principal
+    secondary.lock().unwrap().freeze(&"kiwi".to_string()).unwrap();
+    assert!(cache.put("kiwi".to_string(), "brun".to_string()).is_ok());
+    assert_eq!(shadow.divergence(&cache).failed_writes, 1);
+
+    drop(cache);
+    fs::remove_file(file_path).unwrap();
+    fs::remove_file(shadow_path).unwrap();
+}
+
+#[test]
+fn test_cache_lru_order_after_reused_slots() {
+    let file_path = "test_cache_lru_order_after_reused_slots.txt";
+    let mut cache: CacheDB<String, i32> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+
+    for (index, key) in ["a", "b", "c", "d", "e"].iter().enumerate() {
+        assert!(cache.put(key.to_string(), index as i32).is_ok());
+    }
+    assert_eq!(cache.get(&"c".to_string()), Some(&2));
+    assert!(cache.remove(&"d".to_string()).is_ok());
+    assert!(cache.put("f".to_string(), 5).is_ok());
+    assert!(cache.put("g".to_string(), 6).is_ok());
+
+    // L'ordre d'utilisation est conservé malgré la réutilisation des emplacements libérés
+    let keys: Vec<&String> = cache.iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec!["c", "f", "g"]);
+    let keys: Vec<&String> = cache.iter().rev().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec!["g", "f", "c"]);
+    assert_eq!(cache.iter().count(), 3);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_peek_and_contains_key() {
+    let file_path = "test_cache_peek_and_contains_key.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.capacity(), 2);
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+
+    // Lire sans promouvoir ne modifie ni l'ordre ni le compteur de lectures
+    assert_eq!(cache.peek(&"pomme".to_string()), Some(&"rouge".to_string()));
+    assert!(cache.contains_key(&"pomme".to_string()));
+    assert_eq!(cache.metadata(&"pomme".to_string()).unwrap().hit_count, 0);
+    let keys: Vec<&String> = cache.iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec!["pomme", "banane"]);
+
+    // Un élément expiré est considéré comme absent
+    cache.expire(&"banane".to_string(), None, Some(Duration::ZERO)).unwrap();
+    assert!(!cache.contains_key(&"banane".to_string()));
+    assert_eq!(cache.peek(&"banane".to_string()), None);
+    assert!(!cache.contains_key(&"kiwi".to_string()));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_read_views_skip_expired() {
+    let file_path = "test_cache_read_views_skip_expired.txt";
+    let mut cache: CacheDB<String, i32> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("user:1".to_string(), 1).is_ok());
+    assert!(cache.put("user:2".to_string(), 2).is_ok());
+    assert!(cache.put("user:3".to_string(), 3).is_ok());
+    cache.expire(&"user:2".to_string(), None, Some(Duration::ZERO)).unwrap();
+
+    // Tant qu'il n'est pas supprimé, l'élément expiré est ignoré par toutes les lectures
+    let keys: Vec<&String> = cache.iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec!["user:1", "user:3"]);
+    let keys: Vec<&String> = cache.iter().rev().map(|(key, _)| key).collect();
+    assert_eq!(keys, vec!["user:3", "user:1"]);
+    assert_eq!(cache.len(), 2);
+    assert!(!cache.is_empty());
+    let found: Vec<(usize, &String)> = cache.scan_prefix("user:").into_iter().map(|(position, key, _)| (position, key)).collect();
+    assert_eq!(found, vec![(0, &"user:1".to_string()), (1, &"user:3".to_string())]);
+    assert_eq!(cache.peek_at(1), Some((&"user:3".to_string(), &3)));
+    assert_eq!(cache.peek_at(2), None);
+    assert!(cache.metadata(&"user:2".to_string()).is_none());
+    assert!(cache.ttl(&"user:2".to_string()).is_none());
+    assert!(!cache.contains_key(&"user:2".to_string()));
+    assert_eq!(cache.peek(&"user:2".to_string()), None);
+
+    let mut export = Vec::new();
+    cache.export_to_writer(&mut export, ExportFormat::Json).unwrap();
+    let export: Value = serde_json::from_slice(&export).unwrap();
+    assert_eq!(export, json!([{ "key": "user:1", "value": 1 }, { "key": "user:3", "value": 3 }]));
+
+    // Les positions suivent le même ordre pour l'éviction
+    assert_eq!(cache.evict_at(1).unwrap(), ("user:3".to_string(), 3));
+
+    cache.expire(&"user:1".to_string(), None, Some(Duration::ZERO)).unwrap();
+    assert!(cache.is_empty());
+    assert_eq!(cache.into_iter().count(), 0);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_entry() {
+    let file_path = "test_cache_entry.txt";
+    let mut cache: CacheDB<String, i32> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+
+    // Une clé absente est insérée
+    assert!(matches!(cache.entry("pomme".to_string()), Entry::Vacant(_)));
+    assert_eq!(cache.entry("pomme".to_string()).or_insert_with(|| 1).unwrap(), &1);
+
+    // La closure n'est pas appelée pour une clé présente
+    assert_eq!(cache.entry("pomme".to_string()).or_insert_with(|| panic!("clé présente")).unwrap(), &1);
+
+    // Une modification est persistée une seule fois
+    let persist_count = cache.stats().persist_count;
+    let entry = cache.entry("pomme".to_string()).and_modify(|value| *value += 10).unwrap();
+    assert_eq!(entry.key(), "pomme");
+    assert_eq!(entry.or_insert(0).unwrap(), &11);
+    assert_eq!(cache.stats().persist_count, persist_count + 1);
+
+    // Un élément gelé ne peut pas être modifié
+    cache.freeze(&"pomme".to_string()).unwrap();
+    let result = cache.entry("pomme".to_string()).and_modify(|value| *value += 1);
+    assert!(matches!(result, Err(CustomError::Locked)));
+    drop(cache);
+
+    let mut cache: CacheDB<String, i32> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.peek(&"pomme".to_string()), Some(&11));
+
+    // Un élément qui expire après l'appel à entry reste lisible, puis est considéré comme absent
+    assert!(cache.put_with_ttl("kiwi".to_string(), 5, None, Some(Duration::from_millis(50))).is_ok());
+    match cache.entry("kiwi".to_string()) {
+        Entry::Occupied(entry) => {
+            thread::sleep(Duration::from_millis(100));
+            assert_eq!(entry.get(), &5);
+        }
+        Entry::Vacant(_) => panic!("élément présent"),
+    }
+    assert!(matches!(cache.entry("kiwi".to_string()), Entry::Vacant(_)));
+    assert_eq!(cache.entry("kiwi".to_string()).or_insert(6).unwrap(), &6);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_get_or_insert_with_single_flight() {
+    let file_path = "test_cache_get_or_insert_with_single_flight.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+
+    // La fonction de chargement n'est appelée que pour une clé absente, et ses erreurs sont propagées
+    assert_eq!(cache.get_or_insert_with("pomme".to_string(), || Ok::<_, CustomError>("rouge".to_string())).unwrap(), "rouge");
+    assert_eq!(cache.get_or_insert_with("pomme".to_string(), || Err(CustomError::BadRequest)).unwrap(), "rouge");
+    assert!(matches!(cache.get_or_insert_with("kiwi".to_string(), || Err(CustomError::BadRequest)), Err(CustomError::BadRequest)));
+    assert!(!cache.contains_key(&"kiwi".to_string()));
+
+    // Un seul des threads qui manquent la même clé appelle la fonction de chargement
+    let cache = Arc::new(Mutex::new(cache));
+    let single_flight = Arc::new(SingleFlight::new());
+    let loads = Arc::new(Mutex::new(0));
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let (cache, single_flight, loads) = (cache.clone(), single_flight.clone(), loads.clone());
+            thread::spawn(move || {
+                single_flight.get_or_load(&cache, "banane".to_string(), || {
+                    *loads.lock().unwrap() += 1;
+                    thread::sleep(Duration::from_millis(50));
+                    Ok::<_, CustomError>("jaune".to_string())
+                })
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap().unwrap(), "jaune");
+    }
+    assert_eq!(*loads.lock().unwrap(), 1);
+
+    // Une fonction de chargement qui panique ne bloque pas les appels suivants pour la même clé
+    let panicking = {
+        let (cache, single_flight) = (cache.clone(), single_flight.clone());
+        thread::spawn(move || {
+            single_flight.get_or_load(&cache, "kiwi".to_string(), || -> Result<String, CustomError> { panic!("chargement interrompu") })
+        })
+    };
+    assert!(panicking.join().is_err());
+    let (sender, receiver) = std::sync::mpsc::channel();
+    {
+        let (cache, single_flight) = (cache.clone(), single_flight.clone());
+        thread::spawn(move || {
+            let value = single_flight.get_or_load(&cache, "kiwi".to_string(), || Ok::<_, CustomError>("vert".to_string()));
+            sender.send(value.unwrap()).unwrap();
+        });
+    }
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), "vert");
+
+    drop(cache);
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_std_traits() {
+    let file_path = "test_cache_std_traits.txt";
+    let mut cache: CacheDB<String, i32> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+
+    // Extend persiste le cache une seule fois
+    let persist_count = cache.stats().persist_count;
+    cache.extend(vec![("pomme".to_string(), 1), ("banane".to_string(), 2)]);
+    assert_eq!(cache.stats().persist_count, persist_count + 1);
+
+    let mut keys = Vec::new();
+    for (key, _) in &cache {
+        keys.push(key.clone());
+    }
+    assert_eq!(keys, vec!["pomme", "banane"]);
+    assert_eq!(format!("{:?}", cache), "{\"pomme\": 1, \"banane\": 2}");
+
+    // La copie utilise son propre fichier
+    let mut copy = cache.clone_to("test_cache_std_traits.copy").unwrap();
+    assert!(copy.put("kiwi".to_string(), 3).is_ok());
+    assert_eq!(cache.len(), 2);
+    assert!(matches!(cache.clone_to(file_path), Err(CustomError::BadRequest)));
+
+    // Consommer le cache ne modifie pas son fichier
+    let entries: Vec<(String, i32)> = copy.into_iter().collect();
+    assert_eq!(entries, vec![("pomme".to_string(), 1), ("banane".to_string(), 2), ("kiwi".to_string(), 3)]);
+    let copy: CacheDB<String, i32> = CacheDB::new_persistent(5, "test_cache_std_traits.copy").expect("Erreur lors de la création du cache");
+    assert_eq!(copy.len(), 3);
+
+    fs::remove_file(file_path).unwrap();
+    fs::remove_file("test_cache_std_traits.copy").unwrap();
+}
+
+#[test]
+fn test_cache_resize() {
+    let file_path = "test_cache_resize.txt";
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(3, file_path).expect("Erreur lors de la création du cache");
+    cache.subscribe(EventRecorder(events.clone()));
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.put("kiwi".to_string(), "vert".to_string()).is_ok());
+
+    // Réduire la capacité évince les éléments les moins récemment utilisés
+    assert_eq!(cache.resize(2).unwrap(), 1);
+    assert!(!cache.contains_key(&"pomme".to_string()));
+    assert!(events.lock().unwrap().contains(&"evict pomme=rouge".to_string()));
+    assert_eq!(cache.resize(10).unwrap(), 0);
+    assert_eq!(cache.capacity(), 10);
+    drop(cache);
+
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(10, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 2);
+    drop(cache);
+
+    // Les autres politiques refusent ou limitent la réduction
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent_with_policy(10, file_path, CapacityPolicy::Error).expect("Erreur lors de la création du cache");
+    assert!(matches!(cache.resize(1), Err(CustomError::CacheDbCapacityError)));
+    assert_eq!(cache.capacity(), 10);
+    drop(cache);
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent_with_policy(10, file_path, CapacityPolicy::Grow).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.resize(1).unwrap(), 0);
+    assert_eq!(cache.capacity(), 2);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_pinned_entries() {
+    let file_path = "test_cache_pinned_entries.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("config".to_string(), "v1".to_string()).is_ok());
+    assert!(cache.pin(&"config".to_string()).is_ok());
+    assert!(matches!(cache.pin(&"absent".to_string()), Err(CustomError::NotFound)));
+
+    // L'élément épinglé survit à l'éviction, même s'il est le moins récemment utilisé
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.contains_key(&"config".to_string()));
+    assert!(!cache.contains_key(&"pomme".to_string()));
+
+    // Une insertion échoue lorsque tous les éléments sont épinglés
+    assert!(cache.pin(&"banane".to_string()).is_ok());
+    assert!(matches!(cache.put("kiwi".to_string(), "vert".to_string()), Err(CustomError::CacheDbCapacityError)));
+    assert!(matches!(cache.resize(1), Err(CustomError::CacheDbCapacityError)));
+    assert_eq!(cache.capacity(), 2);
+    drop(cache);
+
+    // L'état épinglé est persisté
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.metadata(&"config".to_string()).unwrap().pinned);
+    assert!(cache.unpin(&"config".to_string()).is_ok());
+    assert!(cache.put("kiwi".to_string(), "vert".to_string()).is_ok());
+    assert!(!cache.contains_key(&"config".to_string()));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_touch() {
+    let file_path = "test_cache_touch.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put_with_ttl("session".to_string(), "jeton".to_string(), None, Some(Duration::from_millis(300))).is_ok());
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(matches!(cache.touch(&"absent".to_string()), Err(CustomError::NotFound)));
+
+    // Le décompte de la durée de vie est relancé, sans compter de lecture
+    thread::sleep(Duration::from_millis(200));
+    let metadata = cache.touch(&"session".to_string()).unwrap();
+    assert_eq!(metadata.hit_count, 0);
+    thread::sleep(Duration::from_millis(200));
+    assert!(cache.contains_key(&"session".to_string()));
+
+    // L'élément devient le plus récemment utilisé
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.contains_key(&"session".to_string()));
+    assert!(!cache.contains_key(&"pomme".to_string()));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_pop_and_take() {
+    let file_path = "test_cache_pop_and_take.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("gelé".to_string(), "a".to_string()).is_ok());
+    assert!(cache.freeze(&"gelé".to_string()).is_ok());
+    assert!(cache.put_with_ttl("expiré".to_string(), "b".to_string(), None, Some(Duration::ZERO)).is_ok());
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.put("kiwi".to_string(), "vert".to_string()).is_ok());
+
+    // Les éléments gelés et expirés ne sont pas retournés
+    assert_eq!(cache.pop_lru().unwrap(), Some(("pomme".to_string(), "rouge".to_string())));
+    assert_eq!(cache.pop_mru().unwrap(), Some(("kiwi".to_string(), "vert".to_string())));
+    assert_eq!(cache.take(&"banane".to_string()).unwrap(), Some("jaune".to_string()));
+    assert_eq!(cache.take(&"banane".to_string()).unwrap(), None);
+    assert!(matches!(cache.take(&"gelé".to_string()), Err(CustomError::Locked)));
+    assert_eq!(cache.pop_lru().unwrap(), None);
+    assert_eq!(cache.len(), 1);
+    drop(cache);
+
+    // Les suppressions sont persistées
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 1);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_retain() {
+    let file_path = "test_cache_retain.txt";
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    cache.subscribe(EventRecorder(events.clone()));
+    assert!(cache.put("user:42:profil".to_string(), "a".to_string()).is_ok());
+    assert!(cache.put("user:42:panier".to_string(), "b".to_string()).is_ok());
+    assert!(cache.put("user:42:gelé".to_string(), "c".to_string()).is_ok());
+    assert!(cache.freeze(&"user:42:gelé".to_string()).is_ok());
+    assert!(cache.put("user:7:profil".to_string(), "d".to_string()).is_ok());
+
+    // Les éléments gelés sont conservés
+    assert_eq!(cache.remove_prefix("user:42:").unwrap(), 2);
+    assert_eq!(cache.retain(|_, _| true).unwrap(), 0);
+    let keys: Vec<_> = cache.iter().map(|(key, _)| key.clone()).collect();
+    assert_eq!(keys, vec!["user:42:gelé".to_string(), "user:7:profil".to_string()]);
+    assert!(events.lock().unwrap().contains(&"remove user:42:profil=a".to_string()));
+    drop(cache);
+
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 2);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_position_access() {
+    let file_path = "test_cache_position_access.txt";
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    cache.subscribe(EventRecorder(events.clone()));
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.put("kiwi".to_string(), "vert".to_string()).is_ok());
+    assert!(cache.pin(&"pomme".to_string()).is_ok());
+
+    // La lecture par position ne modifie pas l'ordre
+    assert_eq!(cache.peek_at(1), Some((&"banane".to_string(), &"jaune".to_string())));
+    assert_eq!(cache.peek_at(0), Some((&"pomme".to_string(), &"rouge".to_string())));
+
+    assert!(matches!(cache.evict_at(0), Err(CustomError::Locked)));
+    assert!(matches!(cache.evict_at(3), Err(CustomError::NotFound)));
+    assert_eq!(cache.evict_at(1).unwrap(), ("banane".to_string(), "jaune".to_string()));
+    assert!(events.lock().unwrap().contains(&"evict banane=jaune".to_string()));
+    assert_eq!(cache.peek_at(1), Some((&"kiwi".to_string(), &"vert".to_string())));
+    drop(cache);
+
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.len(), 2);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_ttl_info() {
+    let file_path = "test_cache_ttl_info.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put_with_ttl("session".to_string(), "jeton".to_string(), None, Some(Duration::from_secs(60))).is_ok());
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put_with_ttl("expiré".to_string(), "a".to_string(), None, Some(Duration::ZERO)).is_ok());
+
+    let session = cache.ttl(&"session".to_string()).unwrap();
+    let remaining = session.remaining.unwrap();
+    assert!(remaining > Duration::from_secs(59) && remaining <= Duration::from_secs(60));
+    assert_eq!(session.expires_at, cache.metadata(&"session".to_string()).unwrap().expires_at());
+
+    let pomme = cache.ttl(&"pomme".to_string()).unwrap();
+    assert_eq!(pomme.remaining, None);
+    assert_eq!(pomme.expires_at, None);
+
+    // Un élément expiré ou absent n'a pas d'informations d'expiration
+    assert_eq!(cache.ttl(&"expiré".to_string()), None);
+    assert_eq!(cache.ttl(&"absent".to_string()), None);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_access_stats() {
+    let file_path = "test_cache_access_stats.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.stats().hit_ratio(), None);
+    assert_eq!(cache.stats().last_persist_at, None);
+
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.put("kiwi".to_string(), "vert".to_string()).is_ok()); // "pomme" est évincé
+    assert_eq!(cache.get(&"kiwi".to_string()), Some(&"vert".to_string()));
+    assert_eq!(cache.get(&"banane".to_string()), Some(&"jaune".to_string()));
+    assert_eq!(cache.get(&"pomme".to_string()), None);
+    assert!(cache.peek(&"kiwi".to_string()).is_some()); // Non compté
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 1, 1));
+    assert!(stats.last_persist_at.is_some());
+    assert!(stats.uptime > Duration::ZERO);
+
+    // Les statistiques peuvent être exportées en JSON
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["hits"], 2);
+    assert_eq!(json["evictions"], 1);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_slowlog() {
+    let file_path = "test_cache_slowlog.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.slowlog().is_empty()); // Journal désactivé
+    drop(cache);
+
+    // Avec un seuil nul, toutes les opérations sont enregistrées
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_slowlog(Duration::ZERO, 3);
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    let names: Vec<_> = cache.slowlog().iter().map(|operation| operation.name).collect();
+    assert_eq!(names, vec!["save", "put"]);
+
+    // Seules les dernières opérations sont conservées
+    assert!(cache.remove(&"pomme".to_string()).is_ok());
+    let names: Vec<_> = cache.slowlog().iter().map(|operation| operation.name).collect();
+    assert_eq!(names, vec!["put", "save", "remove"]);
+
+    // Les écritures avec durée de vie sont aussi mesurées
+    assert!(cache.put_with_ttl("kiwi".to_string(), "vert".to_string(), None, Some(Duration::from_secs(60))).is_ok());
+    let names: Vec<_> = cache.slowlog().iter().map(|operation| operation.name).collect();
+    assert_eq!(names, vec!["remove", "save", "put_with_ttl"]);
+    drop(cache);
+
+    // Un seuil élevé n'enregistre rien
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_slowlog(Duration::from_secs(60), 3);
+    assert!(cache.clear().is_ok());
+    assert!(cache.slowlog().is_empty());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_soft_delete() {
+    let file_path = "test_cache_soft_delete.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_soft_delete(Duration::from_secs(3600));
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+
+    // L'élément supprimé n'est plus visible et ne compte pas dans la capacité
+    assert_eq!(cache.remove(&"pomme".to_string()).unwrap(), "rouge".to_string());
+    assert_eq!(cache.get(&"pomme".to_string()), None);
+    assert_eq!(cache.len(), 1);
+    assert!(cache.put("kiwi".to_string(), "vert".to_string()).is_ok());
+    assert_eq!(cache.len(), 2);
+    assert!(matches!(cache.restore(&"absent".to_string()), Err(CustomError::NotFound)));
+    drop(cache);
+
+    // Les éléments supprimés sont persistés et peuvent être rétablis après rechargement
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_soft_delete(Duration::from_secs(3600));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.restore(&"pomme".to_string()).is_ok());
+    assert_eq!(cache.get(&"pomme".to_string()), Some(&"rouge".to_string()));
+    assert!(!cache.contains_key(&"banane".to_string())); // Évincé par le rétablissement
+    assert!(matches!(cache.restore(&"pomme".to_string()), Err(CustomError::NotFound)));
+
+    // Une nouvelle valeur remplace l'élément supprimé
+    assert!(cache.remove(&"kiwi".to_string()).is_ok());
+    assert!(cache.put("kiwi".to_string(), "jaune".to_string()).is_ok());
+    assert!(matches!(cache.restore(&"kiwi".to_string()), Err(CustomError::NotFound)));
+
+    // Les éléments supprimés ne sont purgés qu'après le délai de grâce
+    assert!(cache.remove(&"kiwi".to_string()).is_ok());
+    assert_eq!(cache.purge_tombstones().unwrap(), 0);
+    drop(cache);
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    assert_eq!(cache.purge_tombstones().unwrap(), 1);
+    assert!(matches!(cache.restore(&"kiwi".to_string()), Err(CustomError::NotFound)));
+
+    // Sans suppression réversible, la suppression est définitive
+    assert!(cache.remove(&"pomme".to_string()).is_ok());
+    assert!(matches!(cache.restore(&"pomme".to_string()), Err(CustomError::NotFound)));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_soft_delete_grace_period() {
+    let file_path = "test_cache_soft_delete_grace_period.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_soft_delete(Duration::from_millis(50));
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.remove(&"pomme".to_string()).is_ok());
+
+    // Une fois le délai de grâce écoulé, l'élément ne peut plus être rétabli, même avant la purge
+    thread::sleep(Duration::from_millis(100));
+    assert!(matches!(cache.restore(&"pomme".to_string()), Err(CustomError::NotFound)));
+    assert_eq!(cache.get(&"pomme".to_string()), None);
+    assert_eq!(cache.purge_tombstones().unwrap(), 1);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_value_history() {
+    let file_path = "test_cache_value_history.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_history(2);
+    for value in ["v1", "v2", "v3", "v4"] {
+        assert!(cache.put("config".to_string(), value.to_string()).is_ok());
+    }
+    assert_eq!(cache.metadata(&"config".to_string()).unwrap().version, 4);
+
+    // Seules les deux dernières versions précédentes sont conservées
+    let versions: Vec<_> = cache.history(&"config".to_string()).iter().map(|previous| (previous.version, previous.value.clone())).collect();
+    assert_eq!(versions, vec![(2, "v2".to_string()), (3, "v3".to_string())]);
+    assert!(cache.history(&"absent".to_string()).is_empty());
+    assert!(matches!(cache.rollback(&"config".to_string(), 1), Err(CustomError::NotFound)));
+    drop(cache);
+
+    // L'historique est persisté
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path)
+        .expect("Erreur lors de la création du cache")
+        .with_history(2);
+    assert_eq!(cache.history(&"config".to_string()).len(), 2);
+    assert!(cache.rollback(&"config".to_string(), 2).is_ok());
+    assert_eq!(cache.get(&"config".to_string()), Some(&"v2".to_string()));
+    assert_eq!(cache.metadata(&"config".to_string()).unwrap().version, 5);
+    let versions: Vec<_> = cache.history(&"config".to_string()).iter().map(|previous| previous.version).collect();
+    assert_eq!(versions, vec![3, 4]);
+    drop(cache);
+
+    // Sans historique, les versions précédentes sont effacées à la prochaine modification
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("config".to_string(), "v6".to_string()).is_ok());
+    assert!(cache.history(&"config".to_string()).is_empty());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_conditional_writes() {
+    let file_path = "test_cache_conditional_writes.txt";
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(5, file_path).expect("Erreur lors de la création du cache");
+    let cache = Arc::new(Mutex::new(cache));
+
+    // Un seul producteur parvient à insérer la clé
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let cache = cache.clone();
+            thread::spawn(move || cache.lock().unwrap().put_if_absent("tâche".to_string(), format!("producteur {}", i)).is_ok())
+        })
+        .collect();
+    let inserted = handles.into_iter().map(|handle| handle.join().unwrap()).filter(|&inserted| inserted).count();
+    assert_eq!(inserted, 1);
+
+    let mut cache = cache.lock().unwrap();
+    assert!(matches!(cache.put_if_version("absent".to_string(), "a".to_string(), 1), Err(CustomError::NotFound)));
+    assert!(cache.put_if_version("tâche".to_string(), "terminée".to_string(), 1).is_ok());
+    assert!(matches!(cache.put_if_version("tâche".to_string(), "refusée".to_string(), 1), Err(CustomError::PreconditionFailed)));
+    assert_eq!(cache.get(&"tâche".to_string()), Some(&"terminée".to_string()));
+
+    // Un élément expiré est considéré comme absent
+    assert!(cache.put_with_ttl("expiré".to_string(), "a".to_string(), None, Some(Duration::ZERO)).is_ok());
+    assert_eq!(cache.put_if_absent("expiré".to_string(), "b".to_string()).unwrap(), 1);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_transaction() {
+    let file_path = "test_cache_transaction.txt";
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok());
+    assert!(cache.put("banane".to_string(), "jaune".to_string()).is_ok());
+    assert!(cache.put("gelé".to_string(), "a".to_string()).is_ok()); // "pomme" est évincé
+    assert!(cache.freeze(&"gelé".to_string()).is_ok());
+    cache.subscribe(EventRecorder(events.clone()));
+
+    // Une transaction qui échoue ne modifie pas le cache et ne notifie pas les observateurs
+    let result = cache.transaction(|tx| {
+        tx.put("kiwi".to_string(), "vert".to_string()).remove("gelé".to_string());
+    });
+    assert!(matches!(result, Err(CustomError::Locked)));
+    let keys: Vec<_> = cache.iter().map(|(key, _)| key.clone()).collect();
+    assert_eq!(keys, vec!["banane".to_string(), "gelé".to_string()]);
+    assert!(events.lock().unwrap().is_empty());
+
+    // Une transaction réussie est notifiée dans l'ordre, évictions comprises
+    assert!(cache
+        .transaction(|tx| {
+            tx.remove("banane".to_string())
+                .put("kiwi".to_string(), "vert".to_string())
+                .put("orange".to_string(), "orange".to_string());
+        })
+        .is_ok());
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            "remove banane=jaune".to_string(),
+            "insert kiwi=vert".to_string(),
+            "insert orange=orange".to_string(),
+            "evict gelé=a".to_string(),
+        ]
+    );
+    assert!(cache.transaction(|_| {}).is_ok());
+    drop(cache);
+
+    let cache: CacheDB<String, String> = CacheDB::new_persistent(2, file_path).expect("Erreur lors de la création du cache");
+    let keys: Vec<_> = cache.iter().map(|(key, _)| key.clone()).collect();
+    assert_eq!(keys, vec!["kiwi".to_string(), "orange".to_string()]);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_cache_watch() {
+    let file_path = "test_cache_watch.txt";
+    let mut cache: CacheDB<String, String> = CacheDB::new_persistent(1, file_path).expect("Erreur lors de la création du cache");
+    let events = cache.watch("session".to_string());
+
+    // Le canal peut être lu depuis un autre thread
+    let reader = thread::spawn(move || events.iter().take(3).collect::<Vec<_>>());
+    assert!(cache.put("session".to_string(), "v1".to_string()).is_ok());
+    assert!(cache.put("session".to_string(), "v2".to_string()).is_ok());
+    assert!(cache.put("pomme".to_string(), "rouge".to_string()).is_ok()); // "session" est évincé
+
+    assert_eq!(
+        reader.join().unwrap(),
+        vec![
+            CacheEvent::Inserted { key: "session".to_string(), value: "v1".to_string() },
+            CacheEvent::Inserted { key: "session".to_string(), value: "v2".to_string() },
+            CacheEvent::Evicted { key: "session".to_string(), value: "v2".to_string() },
+        ]
+    );
+
+    // Les modifications continuent après la fermeture du récepteur, et l'observateur est retiré
+    assert_eq!(cache.stats().listeners, 1);
+    assert!(cache.put("session".to_string(), "v3".to_string()).is_ok());
+    assert_eq!(cache.stats().listeners, 0);
+
+    // Un observateur dont la clé n'a pas changé reste enregistré
+    let events = cache.watch("session".to_string());
+    let _other = cache.watch("pomme".to_string());
+    drop(events);
+    assert!(cache.put("session".to_string(), "v4".to_string()).is_ok());
+    assert_eq!(cache.stats().listeners, 1);
+
+    fs::remove_file(file_path).unwrap();
+}